@@ -0,0 +1,556 @@
+// ============================================================
+// Bitsliced, table-free AES-128
+// ============================================================
+//
+// The state of four blocks is held in eight u64 bit planes: bit `i` of plane `b` is bit `b`
+// of byte `i % 16` (State order, col * 4 + row) of block `i / 16`. SubBytes is the
+// Boyar-Peralta boolean circuit applied to the planes, and ShiftRows / MixColumns are
+// fixed shifts and masks, so no memory access or branch ever depends on key or data.
+
+use crate::State;
+
+/// Blocks processed together by one pass over the bit planes.
+pub const PARALLEL_BLOCKS: usize = 4;
+
+type Planes = [u64; 8];
+
+// Replicates a 16-bit pattern into each of the four block lanes
+const fn lanes(pattern: u64) -> u64 {
+    pattern * 0x0001_0001_0001_0001
+}
+
+// Replicates a 4-bit pattern into every column nibble
+const fn nibbles(pattern: u64) -> u64 {
+    pattern * 0x1111_1111_1111_1111
+}
+
+// Row r of every column in every block
+const ROW_MASKS: [u64; 4] = [nibbles(0x1), nibbles(0x2), nibbles(0x4), nibbles(0x8)];
+
+pub struct BitslicedAES128 {
+    round_keys: [Planes; 11],
+}
+
+impl BitslicedAES128 {
+    pub fn new(key: [u8; 16]) -> Self {
+        let mut round_keys = [[0u64; 8]; 11];
+        for (planes, round_key) in round_keys.iter_mut().zip(key_expansion(key)) {
+            // the same round key goes into every lane
+            *planes = pack(&[round_key; PARALLEL_BLOCKS]);
+        }
+        Self { round_keys }
+    }
+
+    pub fn cipher(&self, state: &mut State) {
+        self.encrypt_blocks(std::slice::from_mut(state));
+    }
+
+    /// Inverse cipher (decryption). Decrypts the state in place.
+    pub fn inv_cipher(&self, state: &mut State) {
+        self.decrypt_blocks(std::slice::from_mut(state));
+    }
+
+    /// Encrypts the blocks in place, `PARALLEL_BLOCKS` at a time.
+    pub fn encrypt_blocks(&self, blocks: &mut [State]) {
+        for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+            let mut q = pack_states(chunk);
+            self.encrypt_planes(&mut q);
+            unpack_states(&q, chunk);
+        }
+    }
+
+    /// Decrypts the blocks in place, `PARALLEL_BLOCKS` at a time.
+    pub fn decrypt_blocks(&self, blocks: &mut [State]) {
+        for chunk in blocks.chunks_mut(PARALLEL_BLOCKS) {
+            let mut q = pack_states(chunk);
+            self.decrypt_planes(&mut q);
+            unpack_states(&q, chunk);
+        }
+    }
+
+    fn encrypt_planes(&self, q: &mut Planes) {
+        add_round_key(q, &self.round_keys[0]);
+
+        for round_key in &self.round_keys[1..10] {
+            sub_bytes(q);
+            shift_rows(q);
+            mix_columns(q);
+            add_round_key(q, round_key);
+        }
+
+        sub_bytes(q);
+        shift_rows(q);
+        add_round_key(q, &self.round_keys[10]);
+    }
+
+    fn decrypt_planes(&self, q: &mut Planes) {
+        add_round_key(q, &self.round_keys[10]);
+
+        for round_key in self.round_keys[1..10].iter().rev() {
+            inv_shift_rows(q);
+            inv_sub_bytes(q);
+            add_round_key(q, round_key);
+            inv_mix_columns(q);
+        }
+
+        inv_shift_rows(q);
+        inv_sub_bytes(q);
+        add_round_key(q, &self.round_keys[0]);
+    }
+}
+
+impl aes::BlockCipher for BitslicedAES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    // The inherent `encrypt_blocks` takes `State`s, so the byte versions are named explicitly
+    fn encrypt_block(&self, block: &mut [u8]) {
+        aes::BlockCipher::encrypt_blocks(self, block);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        aes::BlockCipher::decrypt_blocks(self, block);
+    }
+
+    // Overridden so a run of blocks fills all lanes instead of going one block per pass
+    fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        assert!(blocks.len().is_multiple_of(16), "Input must be a multiple of the block size");
+        for chunk in blocks.chunks_mut(16 * PARALLEL_BLOCKS) {
+            let mut q = pack_bytes(chunk);
+            self.encrypt_planes(&mut q);
+            unpack_bytes(&q, chunk);
+        }
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        assert!(blocks.len().is_multiple_of(16), "Input must be a multiple of the block size");
+        for chunk in blocks.chunks_mut(16 * PARALLEL_BLOCKS) {
+            let mut q = pack_bytes(chunk);
+            self.decrypt_planes(&mut q);
+            unpack_bytes(&q, chunk);
+        }
+    }
+}
+
+// ============================================================
+// Packing between byte blocks and bit planes
+// ============================================================
+
+fn pack(blocks: &[[u8; 16]; PARALLEL_BLOCKS]) -> Planes {
+    let mut q = [0u64; 8];
+    for (blk, block) in blocks.iter().enumerate() {
+        for (i, &byte) in block.iter().enumerate() {
+            let pos = blk * 16 + i;
+            for (bit, plane) in q.iter_mut().enumerate() {
+                *plane |= (((byte >> bit) & 1) as u64) << pos;
+            }
+        }
+    }
+    q
+}
+
+fn unpack(q: &Planes) -> [[u8; 16]; PARALLEL_BLOCKS] {
+    let mut blocks = [[0u8; 16]; PARALLEL_BLOCKS];
+    for (blk, block) in blocks.iter_mut().enumerate() {
+        for (i, byte) in block.iter_mut().enumerate() {
+            let pos = blk * 16 + i;
+            for (bit, plane) in q.iter().enumerate() {
+                *byte |= (((plane >> pos) & 1) as u8) << bit;
+            }
+        }
+    }
+    blocks
+}
+
+// Unused lanes of a short chunk are encrypted as zero blocks and discarded
+fn pack_states(states: &[State]) -> Planes {
+    let mut blocks = [[0u8; 16]; PARALLEL_BLOCKS];
+    for (block, state) in blocks.iter_mut().zip(states) {
+        *block = *state.as_bytes();
+    }
+    pack(&blocks)
+}
+
+fn pack_bytes(bytes: &[u8]) -> Planes {
+    let mut blocks = [[0u8; 16]; PARALLEL_BLOCKS];
+    for (block, chunk) in blocks.iter_mut().zip(bytes.chunks_exact(16)) {
+        block.copy_from_slice(chunk);
+    }
+    pack(&blocks)
+}
+
+fn unpack_bytes(q: &Planes, bytes: &mut [u8]) {
+    for (chunk, block) in bytes.chunks_exact_mut(16).zip(unpack(q)) {
+        chunk.copy_from_slice(&block);
+    }
+}
+
+fn unpack_states(q: &Planes, states: &mut [State]) {
+    for (state, block) in states.iter_mut().zip(unpack(q)) {
+        *state = State::new(block);
+    }
+}
+
+// ============================================================
+// Round operations on bit planes
+// ============================================================
+
+fn add_round_key(q: &mut Planes, round_key: &Planes) {
+    for (plane, key) in q.iter_mut().zip(round_key) {
+        *plane ^= key;
+    }
+}
+
+/// Boyar-Peralta S-box circuit ("A new combinational logic minimization technique with
+/// applications to cryptology", eprint 2009/191): 32 AND and 83 XOR/XNOR gates.
+/// Inputs and outputs are numbered with x0/s0 as the most significant bit.
+fn sbox(q: &mut Planes) {
+    let x0 = q[7];
+    let x1 = q[6];
+    let x2 = q[5];
+    let x3 = q[4];
+    let x4 = q[3];
+    let x5 = q[2];
+    let x6 = q[1];
+    let x7 = q[0];
+
+    // Top linear transformation
+    let y14 = x3 ^ x5;
+    let y13 = x0 ^ x6;
+    let y9 = x0 ^ x3;
+    let y8 = x0 ^ x5;
+    let t0 = x1 ^ x2;
+    let y1 = t0 ^ x7;
+    let y4 = y1 ^ x3;
+    let y12 = y13 ^ y14;
+    let y2 = y1 ^ x0;
+    let y5 = y1 ^ x6;
+    let y3 = y5 ^ y8;
+    let t1 = x4 ^ y12;
+    let y15 = t1 ^ x5;
+    let y20 = t1 ^ x1;
+    let y6 = y15 ^ x7;
+    let y10 = y15 ^ t0;
+    let y11 = y20 ^ y9;
+    let y7 = x7 ^ y11;
+    let y17 = y10 ^ y11;
+    let y19 = y10 ^ y8;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let y18 = x0 ^ y16;
+
+    // Non-linear section (inversion in GF(2^8) through GF(2^4))
+    let t2 = y12 & y15;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t5 = y4 & x7;
+    let t6 = t5 ^ t2;
+    let t7 = y13 & y16;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t12 = y9 & y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let t17 = t4 ^ t14;
+    let t18 = t6 ^ t16;
+    let t19 = t9 ^ t14;
+    let t20 = t11 ^ t16;
+    let t21 = t17 ^ y20;
+    let t22 = t18 ^ y19;
+    let t23 = t19 ^ y21;
+    let t24 = t20 ^ y18;
+
+    let t25 = t21 ^ t22;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let t30 = t23 ^ t24;
+    let t31 = t22 ^ t26;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t34 = t23 ^ t33;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t37 = t36 ^ t34;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+
+    let t41 = t40 ^ t37;
+    let t42 = t29 ^ t33;
+    let t43 = t29 ^ t40;
+    let t44 = t33 ^ t37;
+    let t45 = t42 ^ t41;
+    let z0 = t44 & y15;
+    let z1 = t37 & y6;
+    let z2 = t33 & x7;
+    let z3 = t43 & y16;
+    let z4 = t40 & y1;
+    let z5 = t29 & y7;
+    let z6 = t42 & y11;
+    let z7 = t45 & y17;
+    let z8 = t41 & y10;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z11 = t33 & y4;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z14 = t29 & y2;
+    let z15 = t42 & y9;
+    let z16 = t45 & y14;
+    let z17 = t41 & y8;
+
+    // Bottom linear transformation
+    let t46 = z15 ^ z16;
+    let t47 = z10 ^ z11;
+    let t48 = z5 ^ z13;
+    let t49 = z9 ^ z10;
+    let t50 = z2 ^ z12;
+    let t51 = z2 ^ z5;
+    let t52 = z7 ^ z8;
+    let t53 = z0 ^ z3;
+    let t54 = z6 ^ z7;
+    let t55 = z16 ^ z17;
+    let t56 = z12 ^ t48;
+    let t57 = t50 ^ t53;
+    let t58 = z4 ^ t46;
+    let t59 = z3 ^ t54;
+    let t60 = t46 ^ t57;
+    let t61 = z14 ^ t57;
+    let t62 = t52 ^ t58;
+    let t63 = t49 ^ t58;
+    let t64 = z4 ^ t59;
+    let t65 = t61 ^ t62;
+    let t66 = z1 ^ t63;
+    let s0 = t59 ^ t63;
+    let s6 = t56 ^ !t62;
+    let s7 = t48 ^ !t60;
+    let t67 = t64 ^ t65;
+    let s3 = t53 ^ t66;
+    let s4 = t51 ^ t66;
+    let s5 = t47 ^ t65;
+    let s1 = t64 ^ !s3;
+    let s2 = t55 ^ !t67;
+
+    q[7] = s0;
+    q[6] = s1;
+    q[5] = s2;
+    q[4] = s3;
+    q[3] = s4;
+    q[2] = s5;
+    q[1] = s6;
+    q[0] = s7;
+}
+
+// Inverse of the S-box affine step: b'_i = b_(i+2) ^ b_(i+5) ^ b_(i+7) ^ 0x05_i
+fn inv_affine(q: &mut Planes) {
+    let x = *q;
+    for (i, plane) in q.iter_mut().enumerate() {
+        *plane = x[(i + 2) % 8] ^ x[(i + 5) % 8] ^ x[(i + 7) % 8];
+    }
+    q[0] = !q[0];
+    q[2] = !q[2];
+}
+
+fn sub_bytes(q: &mut Planes) {
+    sbox(q);
+}
+
+// S⁻¹ = A⁻¹ ∘ S ∘ A⁻¹: the forward circuit computes A(x⁻¹), so wrapping it in the inverse
+// affine map on both sides leaves only the field inversion of A⁻¹(y)
+fn inv_sub_bytes(q: &mut Planes) {
+    inv_affine(q);
+    sbox(q);
+    inv_affine(q);
+}
+
+// Rotates every 16-bit block lane right by `k`, i.e. byte i takes byte (i + k) % 16
+fn rotr_lanes(x: u64, k: u32) -> u64 {
+    if k == 0 {
+        return x;
+    }
+    ((x >> k) & lanes(0xffff >> k)) | ((x << (16 - k)) & lanes((0xffff << (16 - k)) & 0xffff))
+}
+
+// Row r of column c takes row r of column c + r
+fn shift_rows(q: &mut Planes) {
+    for plane in q.iter_mut() {
+        let x = *plane;
+        *plane = (x & ROW_MASKS[0])
+            | rotr_lanes(x & ROW_MASKS[1], 4)
+            | rotr_lanes(x & ROW_MASKS[2], 8)
+            | rotr_lanes(x & ROW_MASKS[3], 12);
+    }
+}
+
+fn inv_shift_rows(q: &mut Planes) {
+    for plane in q.iter_mut() {
+        let x = *plane;
+        *plane = (x & ROW_MASKS[0])
+            | rotr_lanes(x & ROW_MASKS[1], 12)
+            | rotr_lanes(x & ROW_MASKS[2], 8)
+            | rotr_lanes(x & ROW_MASKS[3], 4);
+    }
+}
+
+// Row r of every column takes row (r + k) % 4 of the same column
+fn rotate_rows(x: u64, k: u32) -> u64 {
+    ((x >> k) & nibbles(0xf >> k)) | ((x << (4 - k)) & nibbles((0xf << (4 - k)) & 0xf))
+}
+
+fn rotate_rows_planes(q: &Planes, k: u32) -> Planes {
+    let mut r = [0u64; 8];
+    for (out, &plane) in r.iter_mut().zip(q) {
+        *out = rotate_rows(plane, k);
+    }
+    r
+}
+
+// Multiplication by 2 in GF(2^8) on bit planes: shift up one plane and reduce by 0x1b
+fn xtime(q: &Planes) -> Planes {
+    let hi = q[7];
+    [q[7], q[0] ^ hi, q[1], q[2] ^ hi, q[3] ^ hi, q[4], q[5], q[6]]
+}
+
+// 02·a0 ^ 03·a1 ^ a2 ^ a3 = 02·(a0 ^ a1) ^ a1 ^ a2 ^ a3, for every row at once
+fn mix_columns(q: &mut Planes) {
+    let r1 = rotate_rows_planes(q, 1);
+    let r2 = rotate_rows_planes(q, 2);
+    let r3 = rotate_rows_planes(q, 3);
+
+    let mut sum = [0u64; 8];
+    for i in 0..8 {
+        sum[i] = q[i] ^ r1[i];
+    }
+    let doubled = xtime(&sum);
+
+    for i in 0..8 {
+        q[i] = doubled[i] ^ r1[i] ^ r2[i] ^ r3[i];
+    }
+}
+
+// InvMixColumns = MixColumns ∘ circulant(05, 00, 04, 00): add 04·(a_r ^ a_(r+2)) to each row first
+fn inv_mix_columns(q: &mut Planes) {
+    let r2 = rotate_rows_planes(q, 2);
+
+    let mut sum = [0u64; 8];
+    for i in 0..8 {
+        sum[i] = q[i] ^ r2[i];
+    }
+    let quadrupled = xtime(&xtime(&sum));
+
+    for (plane, extra) in q.iter_mut().zip(quadrupled) {
+        *plane ^= extra;
+    }
+    mix_columns(q);
+}
+
+// ============================================================
+// Constant-time key schedule
+// ============================================================
+
+// SubWord through the bitsliced circuit instead of AES_SBOX
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut block = [0u8; 16];
+    block[..4].copy_from_slice(&word);
+    let mut q = pack(&[block; PARALLEL_BLOCKS]);
+    sbox(&mut q);
+    let out = unpack(&q)[0];
+    [out[0], out[1], out[2], out[3]]
+}
+
+fn key_expansion(key: [u8; 16]) -> [[u8; 16]; 11] {
+    const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1B, 0x36];
+
+    let mut words = [[0u8; 4]; 44];
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        word.copy_from_slice(chunk);
+    }
+
+    for i in 4..44 {
+        let mut temp = words[i - 1];
+        if i % 4 == 0 {
+            temp.rotate_left(1);
+            temp = sub_word(temp);
+            temp[0] ^= RCON[i / 4 - 1];
+        }
+        for j in 0..4 {
+            words[i][j] = words[i - 4][j] ^ temp[j];
+        }
+    }
+
+    let mut round_keys = [[0u8; 16]; 11];
+    for (round_key, round_words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+        for (j, word) in round_words.iter().enumerate() {
+            round_key[j * 4..j * 4 + 4].copy_from_slice(word);
+        }
+    }
+    round_keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AES_INV_SBOX, AES_SBOX};
+
+    // Runs every byte value through the circuit, 64 at a time (one per plane bit)
+    fn circuit_table(f: fn(&mut Planes)) -> [u8; 256] {
+        let mut table = [0u8; 256];
+        for base in (0..256).step_by(64) {
+            let mut blocks = [[0u8; 16]; PARALLEL_BLOCKS];
+            for (i, byte) in blocks.iter_mut().flatten().enumerate() {
+                *byte = (base + i) as u8;
+            }
+            let mut q = pack(&blocks);
+            f(&mut q);
+            for (i, &byte) in unpack(&q).iter().flatten().enumerate() {
+                table[base + i] = byte;
+            }
+        }
+        table
+    }
+
+    #[test]
+    fn sbox_circuit_matches_table() {
+        assert_eq!(circuit_table(sub_bytes), AES_SBOX);
+    }
+
+    #[test]
+    fn inv_sbox_circuit_matches_table() {
+        assert_eq!(circuit_table(inv_sub_bytes), AES_INV_SBOX);
+    }
+
+    #[test]
+    fn shift_rows_round_trip() {
+        let blocks = [[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]; PARALLEL_BLOCKS];
+        let mut q = pack(&blocks);
+        shift_rows(&mut q);
+        // column 0 becomes (s00, s11, s22, s33)
+        assert_eq!(unpack(&q)[0][..4], [0x00, 0x55, 0xaa, 0xff]);
+        inv_shift_rows(&mut q);
+        assert_eq!(unpack(&q), blocks);
+    }
+
+    #[test]
+    fn parallel_blocks_match_single_blocks() {
+        let aes = BitslicedAES128::new(*b"YELLOW SUBMARINE");
+        let mut blocks: Vec<State> = (0..7u8).map(|i| State::new([i.wrapping_mul(37); 16])).collect();
+        let originals = blocks.clone();
+
+        aes.encrypt_blocks(&mut blocks);
+        for (encrypted, original) in blocks.iter().zip(&originals) {
+            let mut single = *original;
+            aes.cipher(&mut single);
+            assert_eq!(*encrypted, single);
+        }
+
+        aes.decrypt_blocks(&mut blocks);
+        assert_eq!(blocks, originals);
+    }
+}
@@ -0,0 +1,271 @@
+//! Cache side-channel simulator for the `prime_sboxes` countermeasure.
+//!
+//! The victim's table reads, recorded by `trace` for `AES128` and by
+//! `TTableAES128::cipher_traced` for the T-table backend, are replayed as addresses through a
+//! set-associative LRU cache. A Prime+Probe attacker fills every set with its own lines before
+//! a chosen window of rounds and afterwards re-reads them: a set where one of its lines was
+//! evicted is a set the victim touched.
+//!
+//! The attack is the classic first-round one. Round 1 reads entry `p[i] ^ k[i]` for every
+//! state byte i, so for a guess k the line holding entry `p[i] ^ k` must show up as touched in
+//! every sample. Guesses in the same line as the right key always do; other guesses drop out
+//! once some sample leaves their line alone. What is left after all samples bounds what the
+//! attacker learned, `log2(256 / candidates)` bits per key byte.
+//!
+//! The model assumes the tables lie contiguously at the fixed, line-aligned addresses below,
+//! that the attacker knows them, and that nothing but the victim and attacker uses the cache.
+//! Needs the `trace` feature.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use aes::rng::Rng;
+use aes::ttable::TTableAES128;
+
+use crate::trace::{self, Access, Table};
+use crate::{State, AES128};
+
+/// Where `AES_SBOX` and `AES_INV_SBOX` of this crate lie.
+pub const SBOX_ADDRESS: u64 = 0x1_0000;
+pub const INV_SBOX_ADDRESS: u64 = SBOX_ADDRESS + 256;
+/// Where Te0 to Te3 of the T-table backend lie, 1 KiB each, followed by its S-box.
+pub const TE_ADDRESS: u64 = 0x2_0000;
+pub const TTABLE_SBOX_ADDRESS: u64 = TE_ADDRESS + 4 * 1024;
+// the attacker's eviction buffer starts at the first line from here that falls in set 0
+const ATTACKER_ADDRESS: u64 = 1 << 32;
+
+/// Geometry of a set-associative cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheConfig {
+    pub line_size: usize,
+    pub sets: usize,
+    pub ways: usize,
+}
+
+impl Default for CacheConfig {
+    /// A 32 KiB, 8-way L1 data cache with 64-byte lines.
+    fn default() -> Self {
+        Self { line_size: 64, sets: 64, ways: 8 }
+    }
+}
+
+/// A set-associative cache with LRU replacement. Only which lines are present is modelled.
+pub struct Cache {
+    config: CacheConfig,
+    // line numbers per set, least recently used first
+    sets: Vec<Vec<u64>>,
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Self {
+        assert!(config.line_size > 0 && config.sets > 0 && config.ways > 0, "empty cache geometry");
+        Self { config, sets: vec![Vec::with_capacity(config.ways); config.sets] }
+    }
+
+    pub fn config(&self) -> CacheConfig {
+        self.config
+    }
+
+    pub fn set_of(&self, address: u64) -> usize {
+        (address / self.config.line_size as u64 % self.config.sets as u64) as usize
+    }
+
+    /// Reads `address`, returning whether it hit.
+    pub fn access(&mut self, address: u64) -> bool {
+        let line = address / self.config.line_size as u64;
+        let (ways, set) = (self.config.ways, self.set_of(address));
+        let set = &mut self.sets[set];
+        let hit = match set.iter().position(|&l| l == line) {
+            Some(position) => {
+                set.remove(position);
+                true
+            }
+            None => {
+                if set.len() == ways {
+                    set.remove(0);
+                }
+                false
+            }
+        };
+        set.push(line);
+        hit
+    }
+}
+
+/// A memory read by the victim; `round` is 0 for the key schedule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub round: usize,
+    pub address: u64,
+}
+
+/// An AES-128 implementation whose table reads can be replayed through the cache.
+pub trait Victim {
+    /// Encrypts `plaintext`, returning the addresses read in order.
+    fn encrypt_traced(&self, plaintext: &[u8; 16]) -> Vec<MemoryAccess>;
+
+    /// The address round 1 reads for state byte `position` when that byte is `index`.
+    fn first_round_address(&self, position: usize, index: u8) -> u64;
+}
+
+/// This crate's `AES128`, with or without the `prime_sboxes` scans before each round.
+pub struct SboxVictim {
+    aes: AES128,
+    priming: bool,
+}
+
+impl SboxVictim {
+    /// `priming: false` drops the scans from the trace, which is the cipher without the
+    /// countermeasure: the scans only read the tables and change nothing else.
+    pub fn new(key: [u8; 16], priming: bool) -> Self {
+        Self { aes: AES128::new(key), priming }
+    }
+}
+
+impl Victim for SboxVictim {
+    fn encrypt_traced(&self, plaintext: &[u8; 16]) -> Vec<MemoryAccess> {
+        let mut state = State::new(*plaintext);
+        let ((), accesses) = trace::record(|| self.aes.cipher(&mut state));
+
+        let mut reads = Vec::new();
+        for access in accesses {
+            match access {
+                Access::Lookup { round, table, index } => {
+                    let base = if table == Table::Sbox { SBOX_ADDRESS } else { INV_SBOX_ADDRESS };
+                    reads.push(MemoryAccess { round, address: base + index as u64 });
+                }
+                Access::Scan { round } if self.priming => {
+                    reads.extend((0..512).map(|offset| MemoryAccess { round, address: SBOX_ADDRESS + offset }));
+                }
+                Access::Scan { .. } => {}
+            }
+        }
+        reads
+    }
+
+    fn first_round_address(&self, _position: usize, index: u8) -> u64 {
+        SBOX_ADDRESS + index as u64
+    }
+}
+
+/// The `aes` crate's T-table backend.
+pub struct TTableVictim {
+    aes: TTableAES128,
+}
+
+impl TTableVictim {
+    pub fn new(key: [u8; 16]) -> Self {
+        Self { aes: TTableAES128::new(key) }
+    }
+}
+
+impl Victim for TTableVictim {
+    fn encrypt_traced(&self, plaintext: &[u8; 16]) -> Vec<MemoryAccess> {
+        let mut state = aes::State::new(*plaintext);
+        let mut reads = Vec::new();
+        self.aes.cipher_traced(&mut state, &mut |lookup| {
+            let address = match lookup.table {
+                4 => TTABLE_SBOX_ADDRESS + lookup.index as u64,
+                table => TE_ADDRESS + 1024 * table as u64 + 4 * lookup.index as u64,
+            };
+            reads.push(MemoryAccess { round: lookup.round, address });
+        });
+        reads
+    }
+
+    // byte (row r, column c) goes through Te_r
+    fn first_round_address(&self, position: usize, index: u8) -> u64 {
+        TE_ADDRESS + 1024 * (position % 4) as u64 + 4 * index as u64
+    }
+}
+
+/// A Prime+Probe attacker that primes before round `rounds.start()` and probes after
+/// `rounds.end()`.
+pub struct PrimeProbe {
+    pub cache: CacheConfig,
+    pub rounds: RangeInclusive<usize>,
+}
+
+impl PrimeProbe {
+    /// One sample: prime, let the victim encrypt `plaintext`, probe. Returns the number of
+    /// attacker lines each set lost.
+    pub fn observe(&self, cache: &mut Cache, victim: &dyn Victim, plaintext: &[u8; 16]) -> Vec<usize> {
+        let CacheConfig { sets, ways, .. } = self.cache;
+        for set in 0..sets {
+            for way in 0..ways {
+                cache.access(self.attacker_address(set, way));
+            }
+        }
+
+        for read in victim.encrypt_traced(plaintext) {
+            if self.rounds.contains(&read.round) {
+                cache.access(read.address);
+            }
+        }
+
+        // probing in reverse priming order counts each eviction once: a miss on the least
+        // recently used line would otherwise evict the next one to be probed
+        (0..sets)
+            .map(|set| (0..ways).rev().filter(|&way| !cache.access(self.attacker_address(set, way))).count())
+            .collect()
+    }
+
+    // The `way`th line of the eviction buffer that maps to cache set `set`
+    fn attacker_address(&self, set: usize, way: usize) -> u64 {
+        let CacheConfig { line_size, sets, .. } = self.cache;
+        let first_line = (ATTACKER_ADDRESS / line_size as u64).next_multiple_of(sets as u64);
+        (first_line + (way * sets + set) as u64) * line_size as u64
+    }
+}
+
+/// What the first-round attack learned about each key byte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Leakage {
+    pub samples: usize,
+    /// Key guesses per byte that matched every sample.
+    pub candidates: [usize; 16],
+    /// Whether the right key byte is among them.
+    pub correct: [bool; 16],
+}
+
+impl Leakage {
+    /// Bits of key information recovered: `log2(256 / candidates)` for every byte where the
+    /// right key survived, nothing for the others.
+    pub fn bits(&self) -> f64 {
+        self.candidates.iter().zip(self.correct).filter(|(_, correct)| *correct).map(|(&c, _)| (256.0 / c as f64).log2()).sum()
+    }
+}
+
+impl fmt::Display for Leakage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mean = self.candidates.iter().sum::<usize>() as f64 / 16.0;
+        let correct = self.correct.iter().filter(|&&c| c).count();
+        write!(f, "{:5.1} bits leaked, {:5.1} candidates per byte, {}/16 bytes right", self.bits(), mean, correct)
+    }
+}
+
+/// Runs the first-round attack on `victim` with `samples` random plaintexts from `seed`.
+/// `key` is only used to check which guesses are right.
+pub fn measure(victim: &dyn Victim, key: &[u8; 16], attacker: &PrimeProbe, samples: usize, seed: u64) -> Leakage {
+    let mut cache = Cache::new(attacker.cache);
+    let mut rng = Rng::new(seed);
+    // consistent[i][k]: guess k for byte i has seen its line touched in every sample so far
+    let mut consistent = [[true; 256]; 16];
+
+    for _ in 0..samples {
+        let plaintext: [u8; 16] = rng.bytes();
+        let touched = attacker.observe(&mut cache, victim, &plaintext);
+        for (position, guesses) in consistent.iter_mut().enumerate() {
+            for (guess, consistent) in guesses.iter_mut().enumerate() {
+                let address = victim.first_round_address(position, plaintext[position] ^ guess as u8);
+                *consistent &= touched[cache.set_of(address)] > 0;
+            }
+        }
+    }
+
+    Leakage {
+        samples,
+        candidates: consistent.map(|guesses| guesses.iter().filter(|&&c| c).count()),
+        correct: std::array::from_fn(|i| consistent[i][key[i] as usize]),
+    }
+}
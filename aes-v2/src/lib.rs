@@ -1,6 +1,17 @@
 use std::fmt;
 use std::ops::{BitXor, BitXorAssign, Index, IndexMut};
 
+pub mod bitsliced;
+#[cfg(feature = "trace")]
+pub mod cachesim;
+pub mod masked;
+pub mod trace;
+
+pub use bitsliced::BitslicedAES128;
+pub use masked::MaskedAES128;
+
+use trace::Table;
+
 // ============================================================
 // State representation
 // ============================================================
@@ -61,6 +72,7 @@ impl State {
     }
 
     #[inline]
+    #[allow(clippy::needless_range_loop)]
     pub fn set_col(&mut self, col: usize, values: [u8; 4]) {
         assert!(col < 4, "Column must be between 0 and 3");
         for row in 0..4 {
@@ -228,6 +240,7 @@ pub const fn aes_inv_sbox(x: u8) -> u8 {
 /// subset of them.
 #[inline(never)]
 pub fn prime_sboxes() {
+    trace::scan();
     let mut acc: u8 = 0;
 
     for &v in AES_SBOX.iter() {
@@ -255,19 +268,22 @@ impl AES128 {
     }
 
     pub fn cipher(&self, state: &mut State) {
+        trace::enter_round(0);
         let key_schedule = self.key_expansion(self.key);
 
         self.add_round_key(state, &key_schedule[0]);
 
-        for i in 1..10 {
+        for (round, round_key) in (1..).zip(&key_schedule[1..10]) {
+            trace::enter_round(round);
             prime_sboxes();
 
             self.sub_bytes(state);
             self.shift_rows(state);
             self.mix_columns(state);
-            self.add_round_key(state, &key_schedule[i]);
+            self.add_round_key(state, round_key);
         }
 
+        trace::enter_round(10);
         prime_sboxes();
         self.sub_bytes(state);
         self.shift_rows(state);
@@ -276,11 +292,13 @@ impl AES128 {
 
     /// Inverse cipher (decryption). Decrypts the state in place.
     pub fn inv_cipher(&self, state: &mut State) {
+        trace::enter_round(0);
         let key_schedule = self.key_expansion(self.key);
 
         self.add_round_key(state, &key_schedule[10]);
 
         for i in (1..10).rev() {
+            trace::enter_round(10 - i);
             prime_sboxes();
 
             self.inv_shift_rows(state);
@@ -290,6 +308,7 @@ impl AES128 {
         }
 
         self.inv_shift_rows(state);
+        trace::enter_round(10);
         prime_sboxes();
         self.inv_sub_bytes(state);
         self.add_round_key(state, &key_schedule[0]);
@@ -302,6 +321,7 @@ impl AES128 {
     fn sub_bytes(&self, state: &mut State) {
         for row in 0..4 {
             for col in 0..4 {
+                trace::lookup(Table::Sbox, state[(row, col)]);
                 state[(row, col)] = AES_SBOX[state[(row, col)] as usize];
             }
         }
@@ -310,6 +330,7 @@ impl AES128 {
     fn inv_sub_bytes(&self, state: &mut State) {
         for row in 0..4 {
             for col in 0..4 {
+                trace::lookup(Table::InvSbox, state[(row, col)]);
                 state[(row, col)] = AES_INV_SBOX[state[(row, col)] as usize];
             }
         }
@@ -453,6 +474,9 @@ impl AES128 {
 
     fn sub_word(&self, word: [u8; 4]) -> [u8; 4] {
         let [a, b, c, d] = word;
+        for byte in word {
+            trace::lookup(Table::Sbox, byte);
+        }
         [
             AES_SBOX[a as usize],
             AES_SBOX[b as usize],
@@ -479,6 +503,24 @@ impl AES128 {
     }
 }
 
+impl aes::BlockCipher for AES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let mut state = State::new(block.try_into().expect("AES blocks are 16 bytes"));
+        self.cipher(&mut state);
+        block.copy_from_slice(state.as_bytes());
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let mut state = State::new(block.try_into().expect("AES blocks are 16 bytes"));
+        self.inv_cipher(&mut state);
+        block.copy_from_slice(state.as_bytes());
+    }
+}
+
 // ============================================================
 // ECB helpers
 // ============================================================
@@ -1,30 +1,25 @@
-use aes_v2::{AES128, State};
-use std::io::{self, Read, Write};
+use aes::BlockCipher;
+use aes_v2::{BitslicedAES128, AES128};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let mut stdin = io::stdin().lock();
-    let mut stdout = io::stdout().lock();
+const BITSLICED_OPTION: &str = "      --bitsliced        use the bitsliced AES-128 (constant time, no lookup tables)\n";
 
-    // Read 16-byte key from stdin
-    let mut key = [0u8; 16];
-    stdin.read_exact(&mut key)?;
+// The `aes` command-line tool on this crate's AES-128 backends. Without arguments this is the
+// Kattis submission; see `aes-v2 --help` for the rest.
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
 
-    let aes = AES128::new(key);
-    let mut block = [0u8; 16];
+    // `--bitsliced` selects the table-free constant-time implementation
+    let bitsliced = args.iter().any(|arg| arg == "--bitsliced");
+    args.retain(|arg| arg != "--bitsliced");
 
-    loop {
-        let n = stdin.read(&mut block)?;
-        if n == 0 {
-            break;
+    let backend = |key: &[u8]| -> Option<Box<dyn BlockCipher + Send + Sync>> {
+        let key: [u8; 16] = key.try_into().ok()?;
+        if bitsliced {
+            Some(Box::new(BitslicedAES128::new(key)))
+        } else {
+            Some(Box::new(AES128::new(key)))
         }
-        if n < 16 {
-            break;
-        }
-
-        let mut state = State::new(block);
-        aes.cipher(&mut state);
-        stdout.write_all(state.as_bytes())?;
-    }
-
-    Ok(())
+    };
+    aes::cli::main("aes-v2", BITSLICED_OPTION, &args, &backend)
 }
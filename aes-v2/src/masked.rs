@@ -0,0 +1,211 @@
+// ============================================================
+// First-order Boolean-masked AES-128
+// ============================================================
+//
+// Every value the cipher handles is the real one XORed with a mask that is fresh for each
+// block, so no single intermediate value is correlated with the key (the scheme of Herbst,
+// Oswald and Mangard). Per block the cipher draws an S-box input mask m, an output mask m'
+// and MixColumns masks m1..m4, one per row, and builds the masked table
+// S'[x ^ m] = S[x] ^ m'. The state carries m into SubBytes and m' out of it, is remasked to
+// m1..m4 before MixColumns (with m' in every byte, MixColumns would XOR masks together and
+// cancel them), and each round key is added together with the change back to m, so the key
+// is only ever added masked. The plaintext starts out under m1..m4 for the same reason, so
+// round 0 adds the key with the change from m1..m4 to m. The mask of the state is tracked
+// alongside it and is removed by the last AddRoundKey.
+//
+// This stops first-order DPA/CPA only: an attacker combining two leaking points, say the
+// S-box output and the mask, still sees the key. The key schedule is not masked and the masks
+// come from the xorshift generator of `aes::rng`, which is enough for simulations and not for
+// a real device.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+
+use aes::leakage::{Operation, Step};
+use aes::rng::Rng;
+
+use crate::{State, AES128, AES_INV_SBOX, AES_SBOX};
+
+pub struct MaskedAES128 {
+    aes: AES128,
+    key_schedule: Vec<State>,
+    rng: Mutex<Rng>,
+}
+
+// The masks of one block
+struct Masks {
+    sbox_in: u8,
+    sbox_out: u8,
+    mix_columns: [u8; 4],
+}
+
+impl MaskedAES128 {
+    /// A masked cipher whose masks are seeded from the operating system.
+    pub fn new(key: [u8; 16]) -> Self {
+        Self::with_seed(key, RandomState::new().build_hasher().finish())
+    }
+
+    /// A masked cipher whose masks are reproducible from `seed`.
+    pub fn with_seed(key: [u8; 16], seed: u64) -> Self {
+        let aes = AES128::new(key);
+        let key_schedule = aes.key_expansion(key);
+        Self { aes, key_schedule, rng: Mutex::new(Rng::new(seed)) }
+    }
+
+    pub fn cipher(&self, state: &mut State) {
+        self.encrypt(state, &mut |_| {});
+    }
+
+    pub fn inv_cipher(&self, state: &mut State) {
+        self.decrypt(state, &mut |_| {});
+    }
+
+    /// `cipher`, reporting every step with the masked states a device would actually hold.
+    pub fn cipher_with_leakage(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        self.encrypt(state, leak);
+    }
+
+    fn encrypt(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        let masks = self.fresh_masks();
+        let sbox = masked_table(&AES_SBOX, masks.sbox_in, masks.sbox_out);
+        let (sbox_in, sbox_out) = (State::new([masks.sbox_in; 16]), State::new([masks.sbox_out; 16]));
+        let mix_columns_mask = rows(masks.mix_columns);
+        let mut masked = Masked { state, mask: mix_columns_mask, leak };
+
+        // the plaintext takes a different mask than the one round 0 moves to, or the key added
+        // with that change would be the bare round key
+        *masked.state ^= mix_columns_mask;
+        masked.add_round_key(0, &self.key_schedule[0], sbox_in);
+        for round in 1..=10 {
+            masked.step(round, Operation::SubBytes, &|s| substitute(s, &sbox), &|m| *m = sbox_out);
+            let shift_rows = |s: &mut State| self.aes.shift_rows(s);
+            masked.step(round, Operation::ShiftRows, &shift_rows, &shift_rows);
+            if round < 10 {
+                masked.remask(round, mix_columns_mask);
+                let mix_columns = |s: &mut State| self.aes.mix_columns(s);
+                masked.step(round, Operation::MixColumns, &mix_columns, &mix_columns);
+                masked.add_round_key(round, &self.key_schedule[round], sbox_in);
+            } else {
+                masked.add_round_key(round, &self.key_schedule[round], State::zero());
+            }
+        }
+    }
+
+    fn decrypt(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        // the inverse S-box takes m' to m
+        let masks = self.fresh_masks();
+        let inv_sbox = masked_table(&AES_INV_SBOX, masks.sbox_out, masks.sbox_in);
+        let (sbox_in, sbox_out) = (State::new([masks.sbox_in; 16]), State::new([masks.sbox_out; 16]));
+        let mix_columns_mask = rows(masks.mix_columns);
+        let mut masked = Masked { state, mask: mix_columns_mask, leak };
+
+        *masked.state ^= mix_columns_mask;
+        masked.add_round_key(0, &self.key_schedule[10], sbox_out);
+        for round in 1..=10 {
+            let round_key = &self.key_schedule[10 - round];
+            let inv_shift_rows = |s: &mut State| self.aes.inv_shift_rows(s);
+            masked.step(round, Operation::ShiftRows, &inv_shift_rows, &inv_shift_rows);
+            masked.step(round, Operation::SubBytes, &|s| substitute(s, &inv_sbox), &|m| *m = sbox_in);
+            if round < 10 {
+                masked.add_round_key(round, round_key, mix_columns_mask);
+                let inv_mix_columns = |s: &mut State| self.aes.inv_mix_columns(s);
+                masked.step(round, Operation::MixColumns, &inv_mix_columns, &inv_mix_columns);
+                masked.remask(round, sbox_out);
+            } else {
+                masked.add_round_key(round, round_key, State::zero());
+            }
+        }
+    }
+
+    fn fresh_masks(&self) -> Masks {
+        // behind a lock so threads sharing the cipher never share masks
+        let bytes = self.rng.lock().unwrap().next_u64().to_le_bytes();
+        Masks { sbox_in: bytes[0], sbox_out: bytes[1], mix_columns: [bytes[2], bytes[3], bytes[4], bytes[5]] }
+    }
+}
+
+// A state and the mask it is currently XORed with
+struct Masked<'a> {
+    state: &'a mut State,
+    mask: State,
+    leak: &'a mut dyn FnMut(Step),
+}
+
+impl Masked<'_> {
+    // Applies `f` to the state and `on_mask` to the mask, reporting the masked values
+    fn step(&mut self, round: usize, operation: Operation, f: &dyn Fn(&mut State), on_mask: &dyn Fn(&mut State)) {
+        let before = *self.state;
+        f(self.state);
+        on_mask(&mut self.mask);
+        self.report(round, operation, &before);
+    }
+
+    // Adds the round key and moves to mask `to` in one XOR with the masked key, so neither the
+    // key nor the state is ever unmasked in between
+    fn add_round_key(&mut self, round: usize, round_key: &State, to: State) {
+        let masked_key = *round_key ^ self.mask ^ to;
+        let before = *self.state;
+        *self.state ^= masked_key;
+        self.mask = to;
+        self.report(round, Operation::AddRoundKey, &before);
+    }
+
+    fn remask(&mut self, round: usize, to: State) {
+        let change = self.mask ^ to;
+        let before = *self.state;
+        *self.state ^= change;
+        self.mask = to;
+        self.report(round, Operation::Remask, &before);
+    }
+
+    fn report(&mut self, round: usize, operation: Operation, before: &State) {
+        let before = aes::State::new(*before.as_bytes());
+        let after = aes::State::new(*self.state.as_bytes());
+        (self.leak)(Step { round, operation, before: &before, after: &after });
+    }
+}
+
+// table'[x ^ mask_in] = table[x] ^ mask_out
+fn masked_table(table: &[u8; 256], mask_in: u8, mask_out: u8) -> [u8; 256] {
+    let mut masked = [0u8; 256];
+    for (x, &y) in table.iter().enumerate() {
+        masked[x ^ mask_in as usize] = y ^ mask_out;
+    }
+    masked
+}
+
+fn substitute(state: &mut State, table: &[u8; 256]) {
+    for row in 0..4 {
+        for col in 0..4 {
+            state[(row, col)] = table[state[(row, col)] as usize];
+        }
+    }
+}
+
+// The same byte in every column of each row
+fn rows(bytes: [u8; 4]) -> State {
+    let mut state = State::zero();
+    for col in 0..4 {
+        state.set_col(col, bytes);
+    }
+    state
+}
+
+impl aes::BlockCipher for MaskedAES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let mut state = State::new(block.try_into().expect("AES blocks are 16 bytes"));
+        self.cipher(&mut state);
+        block.copy_from_slice(state.as_bytes());
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let mut state = State::new(block.try_into().expect("AES blocks are 16 bytes"));
+        self.inv_cipher(&mut state);
+        block.copy_from_slice(state.as_bytes());
+    }
+}
@@ -0,0 +1,86 @@
+//! Recording of the S-box accesses made by `AES128`.
+//!
+//! Every S-box lookup in `AES128::cipher`, `inv_cipher` and the key schedule, and every
+//! `prime_sboxes` scan, reports to a per-thread recorder. Outside `record` nothing is kept and
+//! the cost is one thread-local check per lookup. `cachesim` replays these traces through a
+//! cache model.
+//!
+//! The recorder, `record` and `cachesim` exist only with the `trace` feature. Without it the
+//! hooks are empty and the cipher does not touch thread-local state at all.
+
+#[cfg(feature = "trace")]
+use std::cell::RefCell;
+
+/// The table an access reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Table {
+    Sbox,
+    InvSbox,
+}
+
+/// One recorded access. `round` is 0 for the key schedule and counts the cipher rounds from 1.
+#[cfg(feature = "trace")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// One table entry read by a secret-dependent index.
+    Lookup { round: usize, table: Table, index: u8 },
+    /// `prime_sboxes` reading every entry of `AES_SBOX` and then of `AES_INV_SBOX`.
+    Scan { round: usize },
+}
+
+#[cfg(feature = "trace")]
+struct Recorder {
+    round: usize,
+    accesses: Vec<Access>,
+}
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` and returns its result with the accesses it made on this thread.
+#[cfg(feature = "trace")]
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<Access>) {
+    RECORDER.with(|recorder| *recorder.borrow_mut() = Some(Recorder { round: 0, accesses: Vec::new() }));
+    let result = f();
+    let recorder = RECORDER.with(|recorder| recorder.borrow_mut().take()).unwrap();
+    (result, recorder.accesses)
+}
+
+#[cfg(feature = "trace")]
+pub(crate) fn enter_round(round: usize) {
+    with_recorder(|recorder| recorder.round = round);
+}
+
+#[cfg(feature = "trace")]
+pub(crate) fn lookup(table: Table, index: u8) {
+    with_recorder(|recorder| recorder.accesses.push(Access::Lookup { round: recorder.round, table, index }));
+}
+
+#[cfg(feature = "trace")]
+pub(crate) fn scan() {
+    with_recorder(|recorder| recorder.accesses.push(Access::Scan { round: recorder.round }));
+}
+
+#[cfg(feature = "trace")]
+#[inline]
+fn with_recorder(f: impl FnOnce(&mut Recorder)) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            f(recorder);
+        }
+    });
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub(crate) fn enter_round(_round: usize) {}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub(crate) fn lookup(_table: Table, _index: u8) {}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub(crate) fn scan() {}
@@ -0,0 +1,140 @@
+//! Test: the S-box trace and LRU cache model behave as specified, and the Prime+Probe attack
+//! learns nothing through `prime_sboxes` but recovers the line bits of every key byte without
+//! it and from the T-table backend.
+
+use aes_v2::cachesim::{measure, Cache, CacheConfig, MemoryAccess, PrimeProbe, SboxVictim, TTableVictim, Victim};
+use aes_v2::trace::{self, Access, Table};
+use aes_v2::{State, AES128};
+
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+fn first_round(cache: CacheConfig) -> PrimeProbe {
+    PrimeProbe { cache, rounds: 1..=1 }
+}
+
+#[test]
+fn lru_evicts_the_least_recently_used_line() {
+    let mut cache = Cache::new(CacheConfig { line_size: 64, sets: 1, ways: 2 });
+    assert!(!cache.access(0));
+    assert!(!cache.access(64));
+    assert!(cache.access(10), "same line as address 0");
+    assert!(!cache.access(128), "evicts line 1, the least recently used");
+    assert!(cache.access(0));
+    assert!(!cache.access(64));
+}
+
+#[test]
+fn trace_records_every_sbox_access() {
+    let plaintext: [u8; 16] = std::array::from_fn(|i| i as u8);
+    let mut state = State::new(plaintext);
+    let ((), accesses) = trace::record(|| AES128::new(KEY).cipher(&mut state));
+
+    let scans = accesses.iter().filter(|a| matches!(a, Access::Scan { .. })).count();
+    let lookups = |rounds: std::ops::RangeInclusive<usize>| {
+        accesses
+            .iter()
+            .filter_map(|a| match *a {
+                Access::Lookup { round, table: Table::Sbox, index } if rounds.contains(&round) => Some(index),
+                _ => None,
+            })
+            .collect::<Vec<u8>>()
+    };
+    // 10 scans in the key schedule and one before each round
+    assert_eq!(scans, 20);
+    assert_eq!(lookups(0..=0).len(), 40);
+    assert_eq!(lookups(1..=10).len(), 160);
+
+    // round 1 substitutes plaintext ^ key, row by row
+    let expected: Vec<u8> = (0..16).map(|i| (i % 4) * 4 + i / 4).map(|p| plaintext[p] ^ KEY[p]).collect();
+    assert_eq!(lookups(1..=1), expected);
+
+    // nothing is kept outside `record`
+    let ((), accesses) = trace::record(|| {});
+    assert!(accesses.is_empty());
+}
+
+#[test]
+fn probe_counts_the_lines_the_victim_evicted() {
+    struct TwoReads;
+    impl Victim for TwoReads {
+        fn encrypt_traced(&self, _: &[u8; 16]) -> Vec<MemoryAccess> {
+            // two lines in set 3 and a read outside the window
+            vec![
+                MemoryAccess { round: 1, address: 3 * 64 },
+                MemoryAccess { round: 1, address: 3 * 64 + 64 * 64 },
+                MemoryAccess { round: 2, address: 5 * 64 },
+            ]
+        }
+
+        fn first_round_address(&self, _: usize, _: u8) -> u64 {
+            0
+        }
+    }
+
+    let config = CacheConfig::default();
+    let misses = first_round(config).observe(&mut Cache::new(config), &TwoReads, &[0; 16]);
+    assert_eq!(misses.len(), 64);
+    assert_eq!(misses[3], 2);
+    assert_eq!(misses.iter().sum::<usize>(), 2);
+}
+
+#[test]
+fn probes_the_right_sets_when_the_set_count_is_not_a_power_of_two() {
+    struct OneRead;
+    impl Victim for OneRead {
+        fn encrypt_traced(&self, _: &[u8; 16]) -> Vec<MemoryAccess> {
+            vec![MemoryAccess { round: 1, address: 5 * 64 }]
+        }
+
+        fn first_round_address(&self, _: usize, _: u8) -> u64 {
+            0
+        }
+    }
+
+    let config = CacheConfig { line_size: 64, sets: 12, ways: 8 };
+    let misses = first_round(config).observe(&mut Cache::new(config), &OneRead, &[0; 16]);
+    assert_eq!(misses.len(), 12);
+    assert_eq!(misses[5], 1, "misses {:?}", misses);
+    assert_eq!(misses.iter().sum::<usize>(), 1);
+
+    // so the attack still finds the line of every key byte
+    let leakage = measure(&SboxVictim::new(KEY, false), &KEY, &first_round(config), 2000, 2);
+    assert_eq!(leakage.correct, [true; 16]);
+    assert_eq!(leakage.bits(), 32.0);
+}
+
+#[test]
+fn priming_hides_the_first_round() {
+    let config = CacheConfig::default();
+    let leakage = measure(&SboxVictim::new(KEY, true), &KEY, &first_round(config), 200, 1);
+    assert_eq!(leakage.candidates, [256; 16]);
+    assert_eq!(leakage.bits(), 0.0);
+}
+
+#[test]
+fn without_priming_the_first_round_leaks_the_line_of_every_key_byte() {
+    // 64-byte lines hold 64 S-box entries, so the line gives the top 2 bits of each byte
+    let config = CacheConfig::default();
+    let leakage = measure(&SboxVictim::new(KEY, false), &KEY, &first_round(config), 2000, 2);
+    assert_eq!(leakage.correct, [true; 16]);
+    assert_eq!(leakage.candidates, [64; 16]);
+    assert_eq!(leakage.bits(), 32.0);
+
+    // a whole encryption touches all four lines anyway
+    let whole = PrimeProbe { cache: config, rounds: 0..=10 };
+    assert_eq!(measure(&SboxVictim::new(KEY, false), &KEY, &whole, 200, 3).bits(), 0.0);
+}
+
+#[test]
+fn ttable_leaks_even_over_a_whole_encryption() {
+    // 16 four-byte entries per line give the top 4 bits of each byte
+    let config = CacheConfig::default();
+    let whole = PrimeProbe { cache: config, rounds: 0..=10 };
+    let leakage = measure(&TTableVictim::new(KEY), &KEY, &whole, 300, 4);
+    assert_eq!(leakage.correct, [true; 16]);
+    assert_eq!(leakage.bits(), 64.0);
+
+    // smaller lines give away more
+    let config = CacheConfig { line_size: 32, sets: 128, ways: 8 };
+    assert_eq!(measure(&TTableVictim::new(KEY), &KEY, &first_round(config), 300, 5).bits(), 80.0);
+}
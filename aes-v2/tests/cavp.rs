@@ -0,0 +1,40 @@
+//! Test: the 128-bit AESAVS known-answer files from aes/tests/vectors against the byte-oriented and
+//! bitsliced backends. Run with `--nocapture` to see the per-file counts.
+
+use std::path::Path;
+
+use aes::cavp::{run_dir, FileReport};
+use aes::BlockCipher;
+use aes_v2::{BitslicedAES128, AES128};
+
+fn vectors() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../aes/tests/vectors"))
+}
+
+// Prints the report and checks that every 128-bit file ran and passed
+fn check(backend: &str, reports: &[FileReport]) {
+    println!("{}:", backend);
+    for report in reports.iter().filter(|r| r.skipped == 0) {
+        println!("  {}", report);
+    }
+    let failures: Vec<String> = reports
+        .iter()
+        .flat_map(|r| r.failures.iter().map(move |f| format!("{} {}", r.file, f)))
+        .collect();
+    assert!(failures.is_empty(), "{}: {} records failed: {:?}", backend, failures.len(), failures);
+    // 20 known-answer files, plus 10 MMT and MCT files when those are present
+    let ran = reports.iter().filter(|r| r.skipped == 0).count();
+    assert!(matches!(ran, 20 | 30), "{}: expected 20 or 30 AES-128 files, ran {}", backend, ran);
+}
+
+#[test]
+fn aes128_passes_128_bit_files() {
+    let backend = |key: &[u8]| -> Option<Box<dyn BlockCipher + Send + Sync>> { Some(Box::new(AES128::new(key.try_into().ok()?))) };
+    check("aes-v2 AES128", &run_dir(vectors(), &backend).unwrap());
+}
+
+#[test]
+fn bitsliced_passes_128_bit_files() {
+    let backend = |key: &[u8]| -> Option<Box<dyn BlockCipher + Send + Sync>> { Some(Box::new(BitslicedAES128::new(key.try_into().ok()?))) };
+    check("aes-v2 bitsliced", &run_dir(vectors(), &backend).unwrap());
+}
@@ -0,0 +1,15 @@
+//! Test: the `aes-v2` binary answers `--help` with the shared usage text and its own
+//! `--bitsliced` option.
+
+use std::process::Command;
+
+#[test]
+fn help_lists_the_bitsliced_option() {
+    for args in [&["--help"][..], &["encrypt", "--help"], &["--bitsliced", "decrypt", "-h"]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_aes-v2")).args(args).output().unwrap();
+        assert_eq!(output.status.code(), Some(0), "{:?}", args);
+        let help = String::from_utf8(output.stdout).unwrap();
+        assert!(help.starts_with("usage: aes-v2 (encrypt | decrypt)"), "{:?}", args);
+        assert!(help.contains("--bitsliced"));
+    }
+}
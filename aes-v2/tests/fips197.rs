@@ -0,0 +1,47 @@
+//! Test: FIPS-197 Appendix C.1 (AES-128) for both AES-128 implementations.
+
+use aes_v2::{BitslicedAES128, State, AES128};
+
+const KEY: [u8; 16] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const PLAINTEXT: [u8; 16] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+const CIPHERTEXT: [u8; 16] = [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a];
+
+#[test]
+fn appendix_c1_table_based() {
+    let aes = AES128::new(KEY);
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), CIPHERTEXT, "ciphertext must match FIPS-197 C.1");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "inverse cipher must match FIPS-197 C.1");
+}
+
+#[test]
+fn appendix_c1_bitsliced() {
+    let aes = BitslicedAES128::new(KEY);
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), CIPHERTEXT, "ciphertext must match FIPS-197 C.1");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "inverse cipher must match FIPS-197 C.1");
+}
+
+#[test]
+fn appendix_c1_bitsliced_in_parallel() {
+    let aes = BitslicedAES128::new(KEY);
+    let mut blocks = [State::new(PLAINTEXT); 5];
+
+    aes.encrypt_blocks(&mut blocks);
+    for state in &blocks {
+        assert_eq!(*state.as_bytes(), CIPHERTEXT, "every lane must match FIPS-197 C.1");
+    }
+
+    aes.decrypt_blocks(&mut blocks);
+    for state in &blocks {
+        assert_eq!(*state.as_bytes(), PLAINTEXT, "every lane must decrypt back");
+    }
+}
@@ -0,0 +1,89 @@
+//! Test: the masked AES-128 gives the same ciphertexts as the unmasked one, decrypts them
+//! again, never holds the same intermediate values twice for the same block, and never adds
+//! the bare round-0 key.
+
+use aes::leakage::Operation;
+use aes::rng::Rng;
+use aes::BlockCipher;
+use aes_v2::{MaskedAES128, State, AES128};
+
+const KEY: [u8; 16] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f];
+const PLAINTEXT: [u8; 16] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+const CIPHERTEXT: [u8; 16] = [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a];
+
+#[test]
+fn appendix_c1_masked() {
+    let aes = MaskedAES128::new(KEY);
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), CIPHERTEXT, "ciphertext must match FIPS-197 C.1");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "inverse cipher must match FIPS-197 C.1");
+}
+
+#[test]
+fn matches_the_unmasked_cipher() {
+    let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..200 {
+        let key = rng.bytes();
+        let mut block: [u8; 16] = rng.bytes();
+        let (masked, unmasked) = (MaskedAES128::with_seed(key, rng.next_u64()), AES128::new(key));
+
+        let mut expected = block;
+        unmasked.encrypt_block(&mut expected);
+        let plaintext = block;
+        masked.encrypt_block(&mut block);
+        assert_eq!(block, expected);
+        masked.decrypt_block(&mut block);
+        assert_eq!(block, plaintext);
+    }
+}
+
+#[test]
+fn every_block_gets_fresh_masks() {
+    let sbox_outputs = |aes: &MaskedAES128| {
+        let mut state = State::new(PLAINTEXT);
+        let mut outputs = Vec::new();
+        aes.cipher_with_leakage(&mut state, &mut |step| {
+            if step.operation == Operation::SubBytes {
+                outputs.push(*step.after.as_bytes());
+            }
+        });
+        assert_eq!(*state.as_bytes(), CIPHERTEXT);
+        outputs
+    };
+
+    // the unmasked first-round S-box output (FIPS-197 C.1) is never held as such
+    let unmasked = [0x63, 0xca, 0xb7, 0x04, 0x09, 0x53, 0xd0, 0x51, 0xcd, 0x60, 0xe0, 0xe7, 0xba, 0x70, 0xe1, 0x8c];
+    for seed in 1..=20 {
+        let aes = MaskedAES128::with_seed(KEY, seed);
+        let first = sbox_outputs(&aes);
+        let second = sbox_outputs(&aes);
+        assert_eq!(first.len(), 10);
+        assert!(first.iter().zip(&second).all(|(a, b)| a != b), "the S-box outputs must be masked differently");
+
+        let differences: Vec<u8> = first[0].iter().zip(unmasked).map(|(a, b)| a ^ b).collect();
+        assert!(differences.iter().all(|&d| d == differences[0]), "one output mask for the whole state");
+        assert_ne!(differences[0], 0, "seed {}: the S-box output is held unmasked", seed);
+    }
+}
+
+#[test]
+fn the_first_round_key_is_added_masked() {
+    for seed in 1..=20 {
+        let aes = MaskedAES128::with_seed(KEY, seed);
+        let mut state = State::new(PLAINTEXT);
+        let mut added = Vec::new();
+        aes.cipher_with_leakage(&mut state, &mut |step| {
+            if step.round == 0 && step.operation == Operation::AddRoundKey {
+                let (before, after) = (step.before.as_bytes(), step.after.as_bytes());
+                added.push(std::array::from_fn::<u8, 16, _>(|i| before[i] ^ after[i]));
+            }
+        });
+        // the round-0 key is the cipher key itself
+        assert_eq!(added.len(), 1);
+        assert_ne!(added[0], KEY, "seed {}: the key was added unmasked", seed);
+    }
+}
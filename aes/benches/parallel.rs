@@ -0,0 +1,61 @@
+//! Benchmark: multi-threaded bulk ECB encryption, CTR and CBC decryption (MiB/second).
+//!
+//! Run with `cargo bench --bench parallel`. Each mode is timed on a 1 MiB and a 1 GiB buffer,
+//! serially through the regular mode API and then through `aes::parallel` with 1, 2, 4 and
+//! (if different) the available number of threads. The 1 GiB case needs about 2 GiB of memory.
+
+use aes::{ecb_encrypt, parallel, CbcDecryptor, Ctr, Endianness, Padding, AES128};
+use std::hint::black_box;
+use std::time::Instant;
+
+const SIZES: [(&str, usize); 2] = [("1 MiB", 1 << 20), ("1 GiB", 1 << 30)];
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+const IV: [u8; 16] = [0u8; 16];
+
+// Runs `f` once on `len` bytes and returns MiB/second; small buffers are repeated for a
+// measurable duration
+fn mib_per_second<F: FnMut() -> Vec<u8>>(len: usize, mut f: F) -> f64 {
+    let rounds = ((256 << 20) / len).max(1);
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(f());
+    }
+    (rounds * len) as f64 / (1024.0 * 1024.0) / start.elapsed().as_secs_f64()
+}
+
+fn report(size: &str, name: &str, rate: f64) {
+    println!("{:<6} {:<28} {:>10.1} MiB/s", size, name, rate);
+}
+
+fn main() {
+    let aes = AES128::new(KEY);
+    let mut thread_counts = vec![1, 2, 4];
+    let available = parallel::available_threads();
+    if !thread_counts.contains(&available) {
+        thread_counts.push(available);
+    }
+    println!("AES-NI: {}, available threads: {}", aes.uses_aes_ni(), available);
+
+    for (size, len) in SIZES {
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+
+        report(size, "ECB encrypt (serial)", mib_per_second(len, || ecb_encrypt(&aes, &data)));
+        for &threads in &thread_counts {
+            let rate = mib_per_second(len, || parallel::ecb_encrypt(&aes, &data, threads));
+            report(size, &format!("ECB encrypt ({} threads)", threads), rate);
+        }
+
+        report(size, "CTR (serial)", mib_per_second(len, || Ctr::new(&aes, &IV, 16, Endianness::Big).update(&data)));
+        for &threads in &thread_counts {
+            let rate = mib_per_second(len, || parallel::ctr_xor(&aes, &IV, 16, Endianness::Big, &data, threads));
+            report(size, &format!("CTR ({} threads)", threads), rate);
+        }
+
+        let rate = mib_per_second(len, || CbcDecryptor::new(&aes, &IV, Padding::None).update(&data));
+        report(size, "CBC decrypt (serial)", rate);
+        for &threads in &thread_counts {
+            let rate = mib_per_second(len, || parallel::cbc_decrypt(&aes, &IV, &data, Padding::None, threads).unwrap());
+            report(size, &format!("CBC decrypt ({} threads)", threads), rate);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Benchmark: AES block throughput (blocks/second).
+//!
+//! Run with `cargo bench --bench throughput`. Three workloads are measured:
+//! - `cipher` / `inv_cipher` on one long-lived `AES128`, the bulk path used by ECB
+//!   (AES-NI when the CPU has it, and the forced portable path for comparison)
+//! - the same on a portable `AES128` built for every block, which expands the key per block
+//!   as `cipher` did before the round keys were kept: the baseline for the first workload
+//! - one `aes_128_ecb_encrypt` call per block, the pattern cryptopals CBC/CTR use
+//!
+//! With `--features ttable` the T-table backend is measured as well.
+
+use aes::{aes_128_ecb_encrypt, State, AES128};
+use std::hint::black_box;
+use std::time::Instant;
+
+const BLOCKS: usize = 200_000;
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+// Runs `f` once per block and returns the achieved blocks/second
+fn blocks_per_second<F: FnMut(usize)>(blocks: usize, mut f: F) -> f64 {
+    let start = Instant::now();
+    for i in 0..blocks {
+        f(i);
+    }
+    blocks as f64 / start.elapsed().as_secs_f64()
+}
+
+fn report(name: &str, rate: f64) {
+    println!("{:<36} {:>12.0} blocks/s ({:>7.2} MiB/s)", name, rate, rate * 16.0 / (1024.0 * 1024.0));
+}
+
+fn main() {
+    let aes = AES128::new(KEY);
+    let mut state = State::new([0u8; 16]);
+
+    let rate = blocks_per_second(BLOCKS, |_| aes.cipher(black_box(&mut state)));
+    report("AES128::cipher", rate);
+
+    let rate = blocks_per_second(BLOCKS, |_| aes.inv_cipher(black_box(&mut state)));
+    report("AES128::inv_cipher", rate);
+
+    let portable = AES128::new_portable(KEY);
+
+    let rate = blocks_per_second(BLOCKS, |_| portable.cipher(black_box(&mut state)));
+    report("AES128::cipher (portable)", rate);
+
+    let rate = blocks_per_second(BLOCKS, |_| portable.inv_cipher(black_box(&mut state)));
+    report("AES128::inv_cipher (portable)", rate);
+
+    let rate = blocks_per_second(BLOCKS, |_| AES128::new_portable(black_box(KEY)).cipher(black_box(&mut state)));
+    report("AES128::cipher (key per block)", rate);
+
+    let rate = blocks_per_second(BLOCKS, |_| AES128::new_portable(black_box(KEY)).inv_cipher(black_box(&mut state)));
+    report("AES128::inv_cipher (key per block)", rate);
+
+    let block = [0u8; 16];
+    let rate = blocks_per_second(BLOCKS, |_| {
+        black_box(aes_128_ecb_encrypt(black_box(&block), &KEY));
+    });
+    report("aes_128_ecb_encrypt (per block)", rate);
+
+    #[cfg(feature = "ttable")]
+    {
+        let ttable = aes::TTableAES128::new(KEY);
+
+        let rate = blocks_per_second(BLOCKS, |_| ttable.cipher(black_box(&mut state)));
+        report("TTableAES128::cipher", rate);
+
+        let rate = blocks_per_second(BLOCKS, |_| ttable.inv_cipher(black_box(&mut state)));
+        report("TTableAES128::inv_cipher", rate);
+    }
+}
@@ -1,262 +1,453 @@
+use std::sync::OnceLock;
+
+use crate::fault::{encrypt_state_with_fault, Fault};
+use crate::leakage::{encrypt_state_with_leakage, Step};
+use crate::ni;
 use crate::state::State;
 use crate::sbox::{AES_SBOX, AES_INV_SBOX};
-use crate::gf256::{gf256_mul, gf256_mul2, gf256_mul3};
+use crate::gf256::{gf256_mul2, gf256_mul3, xtime};
 
+// The round keys are expanded once in `new`, so the per-block path never touches the heap.
+// `dec_round_keys` holds the schedule for the equivalent inverse cipher (FIPS-197 5.3.5); it is
+// derived on the first decryption, so encrypt-only users such as the ECB helpers never pay for it.
+// `new` picks the AES-NI backend when the CPU has it, see `ni`.
 pub struct AES128 {
-    key: [u8; 16],
+    round_keys: [State; 11],
+    dec_round_keys: OnceLock<[State; 11]>,
+    aes_ni: bool,
 }
 
 impl AES128 {
     pub fn new(key: [u8; 16]) -> Self {
-        Self { key }
-    } 
+        if ni::is_available() {
+            let (round_keys, dec_round_keys) = ni::key_schedule_128(&key);
+            Self { round_keys, dec_round_keys: OnceLock::from(dec_round_keys), aes_ni: true }
+        } else {
+            Self::new_portable(key)
+        }
+    }
+
+    /// Builds the cipher on the portable software path even when AES-NI is available.
+    pub fn new_portable(key: [u8; 16]) -> Self {
+        let round_keys = key_expansion(&key);
+        Self { round_keys, dec_round_keys: OnceLock::new(), aes_ni: false }
+    }
+
+    /// Whether blocks go through the AES-NI instructions.
+    pub fn uses_aes_ni(&self) -> bool {
+        self.aes_ni
+    }
+
+    /// The encryption round keys, round key 0 first, the same as `key_schedule::expand_key`.
+    pub fn round_keys(&self) -> &[State] {
+        &self.round_keys
+    }
+
+    fn dec_round_keys(&self) -> &[State] {
+        self.dec_round_keys.get_or_init(|| inv_key_schedule(&self.round_keys))
+    }
 
     // Since it's aes 128, there are 10 rounds of encryption/decryption
     pub fn cipher(&self, state: &mut State) {
-        let key_schedule = self.key_expansion(self.key);
-        
-        self.add_round_key(state, &key_schedule[0]);
-        
-        // First 9 rounds
-        for i in 1..10 {
-            self.sub_bytes(state);
-            self.shift_rows(state);
-            self.mix_columns(state);
-            self.add_round_key(state, &key_schedule[i]);
+        if self.aes_ni {
+            ni::encrypt_state(state, &self.round_keys);
+        } else {
+            encrypt_state(state, &self.round_keys);
         }
-        
-        // Last round
-        self.sub_bytes(state);
-        self.shift_rows(state);
-        self.add_round_key(state, &key_schedule[10]);
     }
 
     /// Inverse cipher (decryption). Decrypts the state in place.
     pub fn inv_cipher(&self, state: &mut State) {
-        let key_schedule = self.key_expansion(self.key);
+        if self.aes_ni {
+            ni::decrypt_state(state, self.dec_round_keys());
+        } else {
+            decrypt_state(state, self.dec_round_keys());
+        }
+    }
 
-        self.add_round_key(state, &key_schedule[10]);
+    /// `cipher` with a single-byte fault injected, see `fault`. Always runs the portable path.
+    pub fn cipher_with_fault(&self, state: &mut State, fault: Fault) {
+        encrypt_state_with_fault(state, &self.round_keys, fault);
+    }
 
-        // First 9 rounds
-        for i in (1..10).rev() {
-            self.inv_shift_rows(state);
-            self.inv_sub_bytes(state);
-            self.add_round_key(state, &key_schedule[i]);
-            self.inv_mix_columns(state);
-        }
+    /// `cipher` calling `leak` after every step, see `leakage`. Always runs the portable path.
+    pub fn cipher_with_leakage(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        encrypt_state_with_leakage(state, &self.round_keys, leak);
+    }
+}
 
-        // Last round
-        self.inv_shift_rows(state);
-        self.inv_sub_bytes(state);
-        self.add_round_key(state, &key_schedule[0]);
+pub struct AES192 {
+    round_keys: [State; 13],
+    dec_round_keys: OnceLock<[State; 13]>,
+    aes_ni: bool,
+}
+
+impl AES192 {
+    pub fn new(key: [u8; 24]) -> Self {
+        Self { aes_ni: ni::is_available(), ..Self::new_portable(key) }
     }
 
-    // XORs the state with a round-specific subkey derived from the main key via expansion, integrating key material per round
-    fn add_round_key(&self, state: &mut State, round_key: &State) {
-        *state ^= *round_key;
+    /// Builds the cipher on the portable software path even when AES-NI is available.
+    pub fn new_portable(key: [u8; 24]) -> Self {
+        let round_keys = key_expansion(&key);
+        Self { round_keys, dec_round_keys: OnceLock::new(), aes_ni: false }
     }
 
-    // Substitutes each byte using a non-linear S-box lookup table to introduce confusion, ensuring no byte maps to itself or its complement.
-    fn sub_bytes(&self, state: &mut State) {
-        for row in 0..4 {
-            for col in 0..4 {
-                state[(row, col)] = AES_SBOX[state[(row, col)] as usize];
-            }
+    /// Whether blocks go through the AES-NI instructions.
+    pub fn uses_aes_ni(&self) -> bool {
+        self.aes_ni
+    }
+
+    /// The encryption round keys, round key 0 first, the same as `key_schedule::expand_key`.
+    pub fn round_keys(&self) -> &[State] {
+        &self.round_keys
+    }
+
+    fn dec_round_keys(&self) -> &[State] {
+        self.dec_round_keys.get_or_init(|| inv_key_schedule(&self.round_keys))
+    }
+
+    // AES-192 uses a 6-word key and 12 rounds of encryption/decryption
+    pub fn cipher(&self, state: &mut State) {
+        if self.aes_ni {
+            ni::encrypt_state(state, &self.round_keys);
+        } else {
+            encrypt_state(state, &self.round_keys);
         }
     }
 
-    // Substitutes each byte using the inverse S-box lookup table to reverse the confusion introduced by sub_bytes, ensuring each byte maps back to its original value
-    fn inv_sub_bytes(&self, state: &mut State) {
-        for row in 0..4 {
-            for col in 0..4 {
-                state[(row, col)] = AES_INV_SBOX[state[(row, col)] as usize];
-            }
+    /// Inverse cipher (decryption). Decrypts the state in place.
+    pub fn inv_cipher(&self, state: &mut State) {
+        if self.aes_ni {
+            ni::decrypt_state(state, self.dec_round_keys());
+        } else {
+            decrypt_state(state, self.dec_round_keys());
         }
     }
 
-    // Cyclically shifts rows left (0, 1, 2, 3 positions for rows 1-4) to provide diffusion across columns, preventing independent column encryption
-    fn shift_rows(&self, state: &mut State) {
-        // row 0: no shift
-        // row 1: left shift by 1
-        let temp = state[(1, 0)];
-        state[(1, 0)] = state[(1, 1)];
-        state[(1, 1)] = state[(1, 2)];
-        state[(1, 2)] = state[(1, 3)];
-        state[(1, 3)] = temp;
-        
-        // row 2: left shift by 2
-        let temp0 = state[(2, 0)];
-        let temp1 = state[(2, 1)];
-        state[(2, 0)] = state[(2, 2)];
-        state[(2, 1)] = state[(2, 3)];
-        state[(2, 2)] = temp0;
-        state[(2, 3)] = temp1;
-        
-        // row 3: left shift by 3 (or right shift by 1)
-        let temp = state[(3, 3)];
-        state[(3, 3)] = state[(3, 2)];
-        state[(3, 2)] = state[(3, 1)];
-        state[(3, 1)] = state[(3, 0)];
-        state[(3, 0)] = temp;
-    }
-
-    // performs the inverse shift rows operation on all rows of the state, reversing the diffusion introduced by shift rows to ensure consistent decryption
-    fn inv_shift_rows(&self, state: &mut State) {
-        // row 0: no shift
-        // row 1: right shift by 1 (inverse of left by 1)
-        let temp = state[(1, 3)];
-        state[(1, 3)] = state[(1, 2)];
-        state[(1, 2)] = state[(1, 1)];
-        state[(1, 1)] = state[(1, 0)];
-        state[(1, 0)] = temp;
-
-        // row 2: right shift by 2 (same as left by 2, symmetric)
-        let temp0 = state[(2, 0)];
-        let temp1 = state[(2, 1)];
-        state[(2, 0)] = state[(2, 2)];
-        state[(2, 1)] = state[(2, 3)];
-        state[(2, 2)] = temp0;
-        state[(2, 3)] = temp1;
-
-        // row 3: right shift by 3 (inverse of left by 3 = left by 1)
-        let temp = state[(3, 0)];
-        state[(3, 0)] = state[(3, 1)];
-        state[(3, 1)] = state[(3, 2)];
-        state[(3, 2)] = state[(3, 3)];
-        state[(3, 3)] = temp;
-    }
-
-    // performs the MixColumns operation on all columns of the state, mixing the bytes to provide diffusion across columns
-    fn mix_columns(&self, state: &mut State) {
-        for col in 0..4 {
-            let mut column = state.get_col(col);
-            self.mix_column(&mut column);
-            state.set_col(col, column);
+    /// `cipher` with a single-byte fault injected, see `fault`. Always runs the portable path.
+    pub fn cipher_with_fault(&self, state: &mut State, fault: Fault) {
+        encrypt_state_with_fault(state, &self.round_keys, fault);
+    }
+
+    /// `cipher` calling `leak` after every step, see `leakage`. Always runs the portable path.
+    pub fn cipher_with_leakage(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        encrypt_state_with_leakage(state, &self.round_keys, leak);
+    }
+}
+
+pub struct AES256 {
+    round_keys: [State; 15],
+    dec_round_keys: OnceLock<[State; 15]>,
+    aes_ni: bool,
+}
+
+impl AES256 {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { aes_ni: ni::is_available(), ..Self::new_portable(key) }
+    }
+
+    /// Builds the cipher on the portable software path even when AES-NI is available.
+    pub fn new_portable(key: [u8; 32]) -> Self {
+        let round_keys = key_expansion(&key);
+        Self { round_keys, dec_round_keys: OnceLock::new(), aes_ni: false }
+    }
+
+    /// Whether blocks go through the AES-NI instructions.
+    pub fn uses_aes_ni(&self) -> bool {
+        self.aes_ni
+    }
+
+    /// The encryption round keys, round key 0 first, the same as `key_schedule::expand_key`.
+    pub fn round_keys(&self) -> &[State] {
+        &self.round_keys
+    }
+
+    fn dec_round_keys(&self) -> &[State] {
+        self.dec_round_keys.get_or_init(|| inv_key_schedule(&self.round_keys))
+    }
+
+    // AES-256 uses an 8-word key and 14 rounds of encryption/decryption
+    pub fn cipher(&self, state: &mut State) {
+        if self.aes_ni {
+            ni::encrypt_state(state, &self.round_keys);
+        } else {
+            encrypt_state(state, &self.round_keys);
         }
     }
 
-    // performs the MixColumns operation on a single column of the state, mixing the bytes to provide diffusion across columns
-    fn mix_column(&self, vec: &mut [u8]) {  
-        
-        // matrix representation:
-        /* 
-        | 02 03 01 01 |   
-        | 01 02 03 01 |   
-        | 01 01 02 03 |   
-        | 03 01 01 02 |        
-        */
+    /// Inverse cipher (decryption). Decrypts the state in place.
+    pub fn inv_cipher(&self, state: &mut State) {
+        if self.aes_ni {
+            ni::decrypt_state(state, self.dec_round_keys());
+        } else {
+            decrypt_state(state, self.dec_round_keys());
+        }
+    }
 
-        let [a, b, c, d] = [vec[0], vec[1], vec[2], vec[3]];        
+    /// `cipher` with a single-byte fault injected, see `fault`. Always runs the portable path.
+    pub fn cipher_with_fault(&self, state: &mut State, fault: Fault) {
+        encrypt_state_with_fault(state, &self.round_keys, fault);
+    }
 
-        vec[0] = gf256_mul2(a) ^ gf256_mul3(b) ^ c ^ d;
-        vec[1] = a ^ gf256_mul2(b) ^ gf256_mul3(c) ^ d;
-        vec[2] = a ^ b ^ gf256_mul2(c) ^ gf256_mul3(d);
-        vec[3] = gf256_mul3(a) ^ b ^ c ^ gf256_mul2(d);
+    /// `cipher` calling `leak` after every step, see `leakage`. Always runs the portable path.
+    pub fn cipher_with_leakage(&self, state: &mut State, leak: &mut dyn FnMut(Step)) {
+        encrypt_state_with_leakage(state, &self.round_keys, leak);
     }
+}
+
+// runs the forward cipher with the given key schedule, the number of rounds is Nr = round_keys.len() - 1
+fn encrypt_state(state: &mut State, key_schedule: &[State]) {
+    let rounds = key_schedule.len() - 1;
+
+    add_round_key(state, &key_schedule[0]);
+
+    // First Nr - 1 rounds
+    for round_key in &key_schedule[1..rounds] {
+        sub_bytes(state);
+        shift_rows(state);
+        mix_columns(state);
+        add_round_key(state, round_key);
+    }
+
+    // Last round
+    sub_bytes(state);
+    shift_rows(state);
+    add_round_key(state, &key_schedule[rounds]);
+}
+
+// runs the equivalent inverse cipher, `dec_key_schedule` comes from inv_key_schedule so the
+// round structure mirrors encrypt_state (InvSubBytes, InvShiftRows, InvMixColumns, AddRoundKey)
+fn decrypt_state(state: &mut State, dec_key_schedule: &[State]) {
+    let rounds = dec_key_schedule.len() - 1;
 
-    fn inv_mix_columns(&self, state: &mut State) {
+    add_round_key(state, &dec_key_schedule[0]);
+
+    // First Nr - 1 rounds
+    for round_key in &dec_key_schedule[1..rounds] {
+        inv_sub_bytes(state);
+        inv_shift_rows(state);
+        inv_mix_columns(state);
+        add_round_key(state, round_key);
+    }
+
+    // Last round
+    inv_sub_bytes(state);
+    inv_shift_rows(state);
+    add_round_key(state, &dec_key_schedule[rounds]);
+}
+
+// XORs the state with a round-specific subkey derived from the main key via expansion, integrating key material per round
+pub(crate) fn add_round_key(state: &mut State, round_key: &State) {
+    *state ^= *round_key;
+}
+
+// Substitutes each byte using a non-linear S-box lookup table to introduce confusion, ensuring no byte maps to itself or its complement.
+pub(crate) fn sub_bytes(state: &mut State) {
+    for row in 0..4 {
         for col in 0..4 {
-            let mut column = state.get_col(col);
-            self.inv_mix_column(&mut column);
-            state.set_col(col, column);
+            state[(row, col)] = AES_SBOX[state[(row, col)] as usize];
         }
     }
+}
 
-    // performs the inverse MixColumns operation on a single column of the state, reversing the diffusion introduced by MixColumns to ensure consistent decryption
-    fn inv_mix_column(&self, vec: &mut [u8]) {
-        // Inverse MixColumns matrix in GF(2^8):
-        // | 0e 0b 0d 09 |
-        // | 09 0e 0b 0d |
-        // | 0d 09 0e 0b |
-        // | 0b 0d 09 0e |
-        let [a, b, c, d] = [vec[0], vec[1], vec[2], vec[3]];
-
-        vec[0] = gf256_mul(a, 0x0e) ^ gf256_mul(b, 0x0b) ^ gf256_mul(c, 0x0d) ^ gf256_mul(d, 0x09);
-        vec[1] = gf256_mul(a, 0x09) ^ gf256_mul(b, 0x0e) ^ gf256_mul(c, 0x0b) ^ gf256_mul(d, 0x0d);
-        vec[2] = gf256_mul(a, 0x0d) ^ gf256_mul(b, 0x09) ^ gf256_mul(c, 0x0e) ^ gf256_mul(d, 0x0b);
-        vec[3] = gf256_mul(a, 0x0b) ^ gf256_mul(b, 0x0d) ^ gf256_mul(c, 0x09) ^ gf256_mul(d, 0x0e);
-    }
-
-    // generates multiple round keys from the initial cipher key for use in each encryption/decryption round. It  ensures each round has a unique subkey, (diffusion) and preventing simple key reuse attacks
-    fn key_expansion(&self, key: [u8; 16]) -> Vec<State> {
-        // split key into 4 words (w[0..3])
-        let mut words: [[u8; 4]; 44] = [[0; 4]; 44];
-        
-        // initialize first 4 words from key
-        for i in 0..4 {
-            words[i] = [
-                key[i * 4],
-                key[i * 4 + 1],
-                key[i * 4 + 2],
-                key[i * 4 + 3],
-            ];
+// Substitutes each byte using the inverse S-box lookup table to reverse the confusion introduced by sub_bytes, ensuring each byte maps back to its original value
+pub(crate) fn inv_sub_bytes(state: &mut State) {
+    for row in 0..4 {
+        for col in 0..4 {
+            state[(row, col)] = AES_INV_SBOX[state[(row, col)] as usize];
         }
-        
-        // generate remaining 40 words (w[4..43])
-        for i in 4..44 {
-            let mut temp = words[i - 1];
-            
-            if i % 4 == 0 {
-                temp = self.rot_word(temp);
-                temp = self.sub_word(temp);
-                let rcon = self.rcon(i / 4);
-                for j in 0..4 {
-                    temp[j] ^= rcon[j];
-                }
-            }
-            
-            // xor with word 4 positions back
+    }
+}
+
+// Cyclically shifts rows left (0, 1, 2, 3 positions for rows 1-4) to provide diffusion across columns, preventing independent column encryption
+pub(crate) fn shift_rows(state: &mut State) {
+    // row 0: no shift
+    // row 1: left shift by 1
+    let temp = state[(1, 0)];
+    state[(1, 0)] = state[(1, 1)];
+    state[(1, 1)] = state[(1, 2)];
+    state[(1, 2)] = state[(1, 3)];
+    state[(1, 3)] = temp;
+
+    // row 2: left shift by 2
+    let temp0 = state[(2, 0)];
+    let temp1 = state[(2, 1)];
+    state[(2, 0)] = state[(2, 2)];
+    state[(2, 1)] = state[(2, 3)];
+    state[(2, 2)] = temp0;
+    state[(2, 3)] = temp1;
+
+    // row 3: left shift by 3 (or right shift by 1)
+    let temp = state[(3, 3)];
+    state[(3, 3)] = state[(3, 2)];
+    state[(3, 2)] = state[(3, 1)];
+    state[(3, 1)] = state[(3, 0)];
+    state[(3, 0)] = temp;
+}
+
+// performs the inverse shift rows operation on all rows of the state, reversing the diffusion introduced by shift rows to ensure consistent decryption
+pub(crate) fn inv_shift_rows(state: &mut State) {
+    // row 0: no shift
+    // row 1: right shift by 1 (inverse of left by 1)
+    let temp = state[(1, 3)];
+    state[(1, 3)] = state[(1, 2)];
+    state[(1, 2)] = state[(1, 1)];
+    state[(1, 1)] = state[(1, 0)];
+    state[(1, 0)] = temp;
+
+    // row 2: right shift by 2 (same as left by 2, symmetric)
+    let temp0 = state[(2, 0)];
+    let temp1 = state[(2, 1)];
+    state[(2, 0)] = state[(2, 2)];
+    state[(2, 1)] = state[(2, 3)];
+    state[(2, 2)] = temp0;
+    state[(2, 3)] = temp1;
+
+    // row 3: right shift by 3 (inverse of left by 3 = left by 1)
+    let temp = state[(3, 0)];
+    state[(3, 0)] = state[(3, 1)];
+    state[(3, 1)] = state[(3, 2)];
+    state[(3, 2)] = state[(3, 3)];
+    state[(3, 3)] = temp;
+}
+
+// performs the MixColumns operation on all columns of the state, mixing the bytes to provide diffusion across columns
+pub(crate) fn mix_columns(state: &mut State) {
+    for col in 0..4 {
+        let mut column = state.get_col(col);
+        mix_column(&mut column);
+        state.set_col(col, column);
+    }
+}
+
+// performs the MixColumns operation on a single column of the state, mixing the bytes to provide diffusion across columns
+fn mix_column(vec: &mut [u8]) {
+
+    // matrix representation:
+    /*
+    | 02 03 01 01 |
+    | 01 02 03 01 |
+    | 01 01 02 03 |
+    | 03 01 01 02 |
+    */
+
+    let [a, b, c, d] = [vec[0], vec[1], vec[2], vec[3]];
+
+    vec[0] = gf256_mul2(a) ^ gf256_mul3(b) ^ c ^ d;
+    vec[1] = a ^ gf256_mul2(b) ^ gf256_mul3(c) ^ d;
+    vec[2] = a ^ b ^ gf256_mul2(c) ^ gf256_mul3(d);
+    vec[3] = gf256_mul3(a) ^ b ^ c ^ gf256_mul2(d);
+}
+
+pub(crate) fn inv_mix_columns(state: &mut State) {
+    for col in 0..4 {
+        let mut column = state.get_col(col);
+        inv_mix_column(&mut column);
+        state.set_col(col, column);
+    }
+}
+
+// performs the inverse MixColumns operation on a single column of the state, reversing the diffusion introduced by MixColumns to ensure consistent decryption
+fn inv_mix_column(vec: &mut [u8]) {
+    // Inverse MixColumns matrix in GF(2^8):
+    // | 0e 0b 0d 09 |
+    // | 09 0e 0b 0d |
+    // | 0d 09 0e 0b |
+    // | 0b 0d 09 0e |
+    //
+    // which factors as the MixColumns matrix times | 05 00 04 00 | (circulant), so we
+    // premultiply by that cheaper matrix and reuse mix_column
+    let u = xtime(xtime(vec[0] ^ vec[2]));
+    let v = xtime(xtime(vec[1] ^ vec[3]));
+    vec[0] ^= u;
+    vec[1] ^= v;
+    vec[2] ^= u;
+    vec[3] ^= v;
+
+    mix_column(vec);
+}
+
+// generates Nr + 1 round keys from the initial cipher key (FIPS-197 KeyExpansion). The key length picks the variant:
+// Nk = 4, 6 or 8 words for AES-128, AES-192 and AES-256, with Nr = Nk + 6 rounds, so N must be Nk + 7
+pub(crate) fn key_expansion<const N: usize>(key: &[u8]) -> [State; N] {
+    assert!(matches!(key.len(), 16 | 24 | 32), "Key must be 16, 24 or 32 bytes");
+    let nk = key.len() / 4;
+    assert_eq!(N, nk + 7, "AES-{} has {} round keys", key.len() * 8, nk + 7);
+    let total_words = 4 * N;
+
+    // up to 60 words (w[0..59]) for AES-256
+    let mut words: [[u8; 4]; 60] = [[0; 4]; 60];
+
+    // initialize first Nk words from key
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        word.copy_from_slice(chunk);
+    }
+
+    // generate remaining words (w[Nk..4 * (Nr + 1)])
+    for i in nk..total_words {
+        let mut temp = words[i - 1];
+
+        if i % nk == 0 {
+            temp = rot_word(temp);
+            temp = sub_word(temp);
+            let rcon = rcon(i / nk);
             for j in 0..4 {
-                words[i][j] = words[i - 4][j] ^ temp[j];
+                temp[j] ^= rcon[j];
             }
+        } else if nk > 6 && i % nk == 4 {
+            // AES-256 applies an extra SubWord half-way through each key block
+            temp = sub_word(temp);
         }
-        
-        // group every 4 words into a State (11 round keys total)
-        let mut keys = Vec::new();
-        for i in 0..11 {
-            let mut state_data = [0u8; 16];
-            for j in 0..4 {
-                let word = words[i * 4 + j];
-                state_data[j * 4] = word[0];
-                state_data[j * 4 + 1] = word[1];
-                state_data[j * 4 + 2] = word[2];
-                state_data[j * 4 + 3] = word[3];
-            }
-            keys.push(State::new(state_data));
+
+        // xor with word Nk positions back
+        for j in 0..4 {
+            words[i][j] = words[i - nk][j] ^ temp[j];
         }
-        
-        keys
     }
 
-    // rotates the bytes in a word left by one position, effectively shifting the bytes circularly to the left
-    fn rot_word(&self, word: [u8; 4]) -> [u8; 4] {
-        let [a, b, c, d] = word;
-        [b, c, d, a]
+    // group every 4 words into a State (Nr + 1 round keys total)
+    let mut keys = [State::zero(); N];
+    for (round_key, round_words) in keys.iter_mut().zip(words.chunks_exact(4)) {
+        let mut state_data = [0u8; 16];
+        for (j, word) in round_words.iter().enumerate() {
+            state_data[j * 4..j * 4 + 4].copy_from_slice(word);
+        }
+        *round_key = State::new(state_data);
     }
 
-    // substitutes each byte in the word using the AES S-box to introduce confusion, ensuring no byte maps to itself or its complement
-    fn sub_word(&self, word: [u8; 4]) -> [u8; 4] {
-        let [a, b, c, d] = word;
-        [AES_SBOX[a as usize], AES_SBOX[b as usize], AES_SBOX[c as usize], AES_SBOX[d as usize]]
-    }
+    keys
+}
 
-    // generates a round constant for the given round number, used to mix key material into the round key
-    fn rcon(&self, round: usize) -> [u8; 4] {        
-        const RCON_TABLE: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1B, 0x36];
-        
-        if round == 0 {
-            [0x00, 0x00, 0x00, 0x00]
-        } else if round <= 10 {
-            [RCON_TABLE[round - 1], 0x00, 0x00, 0x00]
-        } else {
-            // for round > 10, compute using xtime (2^(round-1))
-            let mut rcon_val = 0x01u8;
-            for _ in 1..round {
-                rcon_val = crate::gf256::xtime(rcon_val);
-            }
-            [rcon_val, 0x00, 0x00, 0x00]
-        }
+// derives the decryption schedule for the equivalent inverse cipher: round keys in reverse order,
+// with InvMixColumns applied to every key except the first and the last
+pub(crate) fn inv_key_schedule<const N: usize>(round_keys: &[State; N]) -> [State; N] {
+    let mut dec_keys = *round_keys;
+    dec_keys.reverse();
+    for round_key in &mut dec_keys[1..N - 1] {
+        inv_mix_columns(round_key);
     }
+    dec_keys
+}
+
+// rotates the bytes in a word left by one position, effectively shifting the bytes circularly to the left
+pub(crate) fn rot_word(word: [u8; 4]) -> [u8; 4] {
+    let [a, b, c, d] = word;
+    [b, c, d, a]
+}
+
+// substitutes each byte in the word using the AES S-box to introduce confusion, ensuring no byte maps to itself or its complement
+pub(crate) fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let [a, b, c, d] = word;
+    [AES_SBOX[a as usize], AES_SBOX[b as usize], AES_SBOX[c as usize], AES_SBOX[d as usize]]
+}
+
+// generates a round constant for the given round number, used to mix key material into the round key.
+// The schedule needs at most 10 of them (AES-128), AES-192 and AES-256 stop at 8 and 7
+pub(crate) fn rcon(round: usize) -> [u8; 4] {
+    const RCON_TABLE: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1B, 0x36];
+
+    [RCON_TABLE[round - 1], 0x00, 0x00, 0x00]
 }
 
 #[cfg(test)]
@@ -279,4 +470,38 @@ mod tests {
 
         assert_eq!(plaintext, decrypted, "decrypt(cipher(plaintext)) should equal plaintext");
     }
+
+    #[test]
+    #[should_panic(expected = "round keys")]
+    fn key_expansion_rejects_wrong_round_key_count() {
+        let _: [State; 11] = key_expansion(&[0u8; 32]);
+    }
+
+    #[test]
+    fn inv_key_schedule_reverses_and_mixes() {
+        let round_keys: [State; 11] = key_expansion(&[0x2b; 16]);
+        let dec_keys = inv_key_schedule(&round_keys);
+
+        assert_eq!(dec_keys[0], round_keys[10]);
+        assert_eq!(dec_keys[10], round_keys[0]);
+
+        let mut expected = round_keys[7];
+        inv_mix_columns(&mut expected);
+        assert_eq!(dec_keys[3], expected);
+    }
+
+    #[test]
+    fn key_expansion_last_round_key_aes256() {
+        // FIPS-197 Appendix A.3: w[56..59]
+        let key: [u8; 32] = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81,
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4,
+        ];
+        let expected: [u8; 16] = [
+            0xfe, 0x48, 0x90, 0xd1, 0xe6, 0x18, 0x8d, 0x0b, 0x04, 0x6d, 0xf3, 0x44, 0x70, 0x6c, 0x63, 0x1e,
+        ];
+
+        let schedule: [State; 15] = key_expansion(&key);
+        assert_eq!(*schedule[14].as_bytes(), expected);
+    }
 }
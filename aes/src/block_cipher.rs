@@ -0,0 +1,152 @@
+use crate::{AES128, AES192, AES256, State};
+
+/// A keyed block cipher, the common interface modes and attacks are written against.
+///
+/// Blocks are passed as byte slices of exactly `block_size()` bytes, so toy ciphers with
+/// other block sizes can be plugged into the same code as AES.
+pub trait BlockCipher {
+    /// Block size in bytes.
+    fn block_size(&self) -> usize;
+
+    /// Encrypts one block in place. `block.len()` must equal `block_size()`.
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypts one block in place. `block.len()` must equal `block_size()`.
+    fn decrypt_block(&self, block: &mut [u8]);
+
+    /// Encrypts consecutive blocks in place. `blocks.len()` must be a multiple of `block_size()`.
+    fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        let block_size = self.block_size();
+        assert!(blocks.len().is_multiple_of(block_size), "Input must be a multiple of the block size");
+        for block in blocks.chunks_exact_mut(block_size) {
+            self.encrypt_block(block);
+        }
+    }
+
+    /// Decrypts consecutive blocks in place. `blocks.len()` must be a multiple of `block_size()`.
+    fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        let block_size = self.block_size();
+        assert!(blocks.len().is_multiple_of(block_size), "Input must be a multiple of the block size");
+        for block in blocks.chunks_exact_mut(block_size) {
+            self.decrypt_block(block);
+        }
+    }
+}
+
+impl<T: BlockCipher + ?Sized> BlockCipher for &T {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block)
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        (**self).encrypt_blocks(blocks)
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        (**self).decrypt_blocks(blocks)
+    }
+}
+
+impl<T: BlockCipher + ?Sized> BlockCipher for Box<T> {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block)
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut [u8]) {
+        (**self).encrypt_blocks(blocks)
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut [u8]) {
+        (**self).decrypt_blocks(blocks)
+    }
+}
+
+/// Builds the cipher under test from a key, or `None` if the backend does not take keys of
+/// that length. Lets the CAVP runner and the command-line tool work with any implementation.
+/// The cipher is `Sync` so the streaming pipeline can share it between worker threads.
+pub type Backend<'a> = &'a dyn Fn(&[u8]) -> Option<Box<dyn BlockCipher + Send + Sync>>;
+
+/// AES-128, -192 or -256 depending on the key length, as a `Backend`.
+pub fn aes_for_key(key: &[u8]) -> Option<Box<dyn BlockCipher + Send + Sync>> {
+    Some(match key.len() {
+        16 => Box::new(AES128::new(key.try_into().unwrap())),
+        24 => Box::new(AES192::new(key.try_into().unwrap())),
+        32 => Box::new(AES256::new(key.try_into().unwrap())),
+        _ => return None,
+    })
+}
+
+/// Runs `f` on a 16-byte block viewed as an AES `State`, writing the result back.
+pub fn with_state<F>(block: &mut [u8], f: F)
+where
+    F: FnOnce(&mut State),
+{
+    let mut state = State::new(block.try_into().expect("AES blocks are 16 bytes"));
+    f(&mut state);
+    block.copy_from_slice(state.as_bytes());
+}
+
+/// XORs `src` into `dst` byte by byte, over the length of the shorter one.
+pub(crate) fn xor_in_place(dst: &mut [u8], src: &[u8]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+impl BlockCipher for AES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.cipher(state));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.inv_cipher(state));
+    }
+}
+
+impl BlockCipher for AES192 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.cipher(state));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.inv_cipher(state));
+    }
+}
+
+impl BlockCipher for AES256 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.cipher(state));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.inv_cipher(state));
+    }
+}
@@ -0,0 +1,251 @@
+//! Runner for NIST CAVP AESAVS response files (`.rsp`).
+//!
+//! Handles the known-answer files (GFSbox, KeySbox, VarTxt, VarKey), the multi-block message
+//! tests (MMT) and the Monte Carlo tests (MCT) for ECB, CBC, OFB, CFB8 and CFB128. The mode
+//! and test type come from the file name, as in NIST's archives (`CBCMMT192.rsp`,
+//! `CFB8MCT128.rsp`, ...). Every record is run through the streaming mode implementations
+//! with a cipher supplied by the caller, so the same files check every backend.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::block_cipher::{Backend, BlockCipher};
+use crate::cbc::{CbcDecryptor, CbcEncryptor, Padding};
+use crate::cfb::{CfbDecryptor, CfbEncryptor, CfbSegment};
+use crate::ecb::{ecb_decrypt, ecb_encrypt};
+use crate::encoding::from_hex;
+use crate::ofb::Ofb;
+
+const TEST_KINDS: [&str; 6] = ["GFSbox", "KeySbox", "VarTxt", "VarKey", "MMT", "MCT"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+    Ofb,
+    Cfb8,
+    Cfb128,
+}
+
+impl Mode {
+    // bytes per step of a Monte Carlo test
+    fn segment_len(self) -> usize {
+        if self == Mode::Cfb8 { 1 } else { 16 }
+    }
+}
+
+/// One COUNT record. For `[DECRYPT]` records the ciphertext is the input.
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    pub encrypt: bool,
+    pub count: usize,
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+    pub plaintext: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RspFile {
+    pub name: String,
+    /// `None` for modes the runner does not implement (CFB1); all records are then skipped.
+    pub mode: Option<Mode>,
+    pub monte_carlo: bool,
+    pub cases: Vec<TestCase>,
+}
+
+/// Outcome of one file against one backend.
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub file: String,
+    pub passed: usize,
+    pub skipped: usize,
+    /// `ENCRYPT COUNT = n` / `DECRYPT COUNT = n` for every record that did not match.
+    pub failures: Vec<String>,
+}
+
+impl FileReport {
+    pub fn failed(&self) -> usize {
+        self.failures.len()
+    }
+}
+
+impl fmt::Display for FileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} passed, {} failed", self.file, self.passed, self.failed())?;
+        if self.skipped > 0 {
+            write!(f, ", {} skipped", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
+impl RspFile {
+    pub fn load(path: &Path) -> io::Result<RspFile> {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        RspFile::parse(name, &fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of a response file called `name`.
+    pub fn parse(name: &str, text: &str) -> io::Result<RspFile> {
+        let (mode, monte_carlo) = classify(name)
+            .ok_or_else(|| invalid(format!("{}: not an AESAVS file name", name)))?;
+
+        let mut cases = Vec::new();
+        let mut encrypt = None;
+        let mut current: Option<TestCase> = None;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                encrypt = match line {
+                    "[ENCRYPT]" => Some(true),
+                    "[DECRYPT]" => Some(false),
+                    _ => return Err(invalid(format!("{}:{}: unknown section {}", name, number + 1, line))),
+                };
+                continue;
+            }
+
+            let (field, value) = line
+                .split_once('=')
+                .map(|(f, v)| (f.trim(), v.trim()))
+                .ok_or_else(|| invalid(format!("{}:{}: expected FIELD = value", name, number + 1)))?;
+            let bad_value = || invalid(format!("{}:{}: bad value for {}", name, number + 1, field));
+            if field == "COUNT" {
+                let encrypt = encrypt.ok_or_else(|| invalid(format!("{}:{}: COUNT outside a section", name, number + 1)))?;
+                cases.extend(current.take());
+                let count = value.parse().map_err(|_| bad_value())?;
+                current = Some(TestCase { encrypt, count, key: Vec::new(), iv: Vec::new(), plaintext: Vec::new(), ciphertext: Vec::new() });
+                continue;
+            }
+
+            let case = current.as_mut().ok_or_else(|| invalid(format!("{}:{}: {} before COUNT", name, number + 1, field)))?;
+            let bytes = from_hex(value).ok_or_else(bad_value)?;
+            match field {
+                "KEY" => case.key = bytes,
+                "IV" => case.iv = bytes,
+                "PLAINTEXT" => case.plaintext = bytes,
+                "CIPHERTEXT" => case.ciphertext = bytes,
+                _ => return Err(invalid(format!("{}:{}: unknown field {}", name, number + 1, field))),
+            }
+        }
+        cases.extend(current);
+
+        Ok(RspFile { name: name.to_string(), mode, monte_carlo, cases })
+    }
+
+    /// Runs every record against `backend`. Records it cannot key are counted as skipped.
+    pub fn run(&self, backend: Backend<'_>) -> FileReport {
+        let mut report = FileReport { file: self.name.clone(), passed: 0, skipped: 0, failures: Vec::new() };
+        for case in &self.cases {
+            let (Some(mode), Some(cipher)) = (self.mode, backend(&case.key)) else {
+                report.skipped += 1;
+                continue;
+            };
+            let expected = if case.encrypt { &case.ciphertext } else { &case.plaintext };
+            let actual = if self.monte_carlo {
+                monte_carlo(mode, &*cipher, case)
+            } else {
+                let input = if case.encrypt { &case.plaintext } else { &case.ciphertext };
+                Some(stream(mode, &*cipher, &case.iv, case.encrypt)(input))
+            };
+
+            if actual.as_ref() == Some(expected) {
+                report.passed += 1;
+            } else {
+                let section = if case.encrypt { "ENCRYPT" } else { "DECRYPT" };
+                report.failures.push(format!("{} COUNT = {}", section, case.count));
+            }
+        }
+        report
+    }
+}
+
+/// Runs every `.rsp` file in `dir`, in file-name order.
+pub fn run_dir(dir: &Path, backend: Backend<'_>) -> io::Result<Vec<FileReport>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "rsp"));
+    paths.sort();
+
+    paths.iter().map(|path| Ok(RspFile::load(path)?.run(backend))).collect()
+}
+
+// (mode, is Monte Carlo) from a name like CFB128VarKey256.rsp; the mode is `None` for CFB1
+fn classify(name: &str) -> Option<(Option<Mode>, bool)> {
+    let (position, kind) = TEST_KINDS.iter().filter_map(|kind| Some((name.find(kind)?, *kind))).min()?;
+    let mode = match &name[..position] {
+        "ECB" => Some(Mode::Ecb),
+        "CBC" => Some(Mode::Cbc),
+        "OFB" => Some(Mode::Ofb),
+        "CFB8" => Some(Mode::Cfb8),
+        "CFB128" => Some(Mode::Cfb128),
+        "CFB1" => None,
+        _ => return None,
+    };
+    Some((mode, kind == "MCT"))
+}
+
+// The mode as a stream that keeps its chaining state between calls
+type Stream<'a> = Box<dyn FnMut(&[u8]) -> Vec<u8> + 'a>;
+
+fn stream<'a>(mode: Mode, cipher: &'a dyn BlockCipher, iv: &[u8], encrypt: bool) -> Stream<'a> {
+    match (mode, encrypt) {
+        (Mode::Ecb, true) => Box::new(move |input| ecb_encrypt(cipher, input)),
+        (Mode::Ecb, false) => Box::new(move |input| ecb_decrypt(cipher, input)),
+        (Mode::Cbc, true) => {
+            let mut cbc = CbcEncryptor::new(cipher, iv, Padding::None);
+            Box::new(move |input| cbc.update(input))
+        }
+        (Mode::Cbc, false) => {
+            let mut cbc = CbcDecryptor::new(cipher, iv, Padding::None);
+            Box::new(move |input| cbc.update(input))
+        }
+        (Mode::Ofb, _) => {
+            let mut ofb = Ofb::new(cipher, iv);
+            Box::new(move |input| ofb.update(input))
+        }
+        (Mode::Cfb8 | Mode::Cfb128, _) => {
+            let segment = if mode == Mode::Cfb8 { CfbSegment::Bits8 } else { CfbSegment::FullBlock };
+            if encrypt {
+                let mut cfb = CfbEncryptor::new(cipher, iv, segment);
+                Box::new(move |input| cfb.update(input))
+            } else {
+                let mut cfb = CfbDecryptor::new(cipher, iv, segment);
+                Box::new(move |input| cfb.update(input))
+            }
+        }
+    }
+}
+
+// One Monte Carlo record (AESAVS §6.4): 1000 chained operations, returning the last output.
+// ECB feeds each output straight back in. The other modes feed back the IV segments and then
+// the outputs, so after the IV runs out the next input is the output from one step before
+// (CBC, OFB, CFB128) or 16 steps before (CFB8). The key and IV of every record are in the
+// file, so each record is checked on its own.
+fn monte_carlo(mode: Mode, cipher: &dyn BlockCipher, case: &TestCase) -> Option<Vec<u8>> {
+    let segment = mode.segment_len();
+    let mut feedback: Vec<Vec<u8>> = if mode == Mode::Ecb { Vec::new() } else { case.iv.chunks(segment).map(<[u8]>::to_vec).collect() };
+    let mut input = if case.encrypt { case.plaintext.clone() } else { case.ciphertext.clone() };
+    if input.len() != segment {
+        return None;
+    }
+
+    let mut step = stream(mode, cipher, &case.iv, case.encrypt);
+    let mut output = Vec::new();
+    for j in 0..1000 {
+        output = step(&input);
+        feedback.push(output.clone());
+        input = if mode == Mode::Ecb { output.clone() } else { feedback[j].clone() };
+    }
+    Some(output)
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
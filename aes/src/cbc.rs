@@ -0,0 +1,127 @@
+//! Cipher block chaining (SP 800-38A §6.2).
+//!
+//! Both directions buffer input until a whole block is available, so `update` can be fed
+//! chunks of any size. The decryptor holds back the last block when padding is enabled,
+//! because only `finalize` knows which block is the padded one.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::error::ModeError;
+
+/// How the last block is filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// No padding: the total input must be a multiple of the block size.
+    None,
+    /// PKCS#7: always adds 1..=block_size bytes, each equal to the padding length.
+    Pkcs7,
+}
+
+pub struct CbcEncryptor<C: BlockCipher> {
+    cipher: C,
+    prev: Vec<u8>,
+    buffer: Vec<u8>,
+    padding: Padding,
+}
+
+impl<C: BlockCipher> CbcEncryptor<C> {
+    /// `iv` must be exactly one block long.
+    pub fn new(cipher: C, iv: &[u8], padding: Padding) -> Self {
+        assert_eq!(iv.len(), cipher.block_size(), "IV must be one block long");
+        Self { cipher, prev: iv.to_vec(), buffer: Vec::new(), padding }
+    }
+
+    /// Encrypts every whole block available so far and returns the ciphertext.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        let block_size = self.cipher.block_size();
+        self.buffer.extend_from_slice(input);
+        let whole = self.buffer.len() - self.buffer.len() % block_size;
+
+        let mut output: Vec<u8> = self.buffer.drain(..whole).collect();
+        for block in output.chunks_exact_mut(block_size) {
+            xor_in_place(block, &self.prev);
+            self.cipher.encrypt_block(block);
+            self.prev.copy_from_slice(block);
+        }
+        output
+    }
+
+    /// Pads and encrypts what is left. Without padding, a leftover partial block is an error.
+    pub fn finalize(mut self) -> Result<Vec<u8>, ModeError> {
+        match self.padding {
+            Padding::None if !self.buffer.is_empty() => Err(ModeError::PartialBlock),
+            Padding::None => Ok(Vec::new()),
+            Padding::Pkcs7 => {
+                let pad = self.cipher.block_size() - self.buffer.len();
+                self.buffer.resize(self.buffer.len() + pad, pad as u8);
+                Ok(self.update(&[]))
+            }
+        }
+    }
+}
+
+pub struct CbcDecryptor<C: BlockCipher> {
+    cipher: C,
+    prev: Vec<u8>,
+    buffer: Vec<u8>,
+    padding: Padding,
+}
+
+impl<C: BlockCipher> CbcDecryptor<C> {
+    /// `iv` must be exactly one block long.
+    pub fn new(cipher: C, iv: &[u8], padding: Padding) -> Self {
+        assert_eq!(iv.len(), cipher.block_size(), "IV must be one block long");
+        Self { cipher, prev: iv.to_vec(), buffer: Vec::new(), padding }
+    }
+
+    /// Decrypts every whole block that is known not to be the last one and returns the plaintext.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        let block_size = self.cipher.block_size();
+        self.buffer.extend_from_slice(input);
+        let whole = match self.padding {
+            Padding::None => self.buffer.len() - self.buffer.len() % block_size,
+            // keep 1..=block_size bytes back for finalize
+            Padding::Pkcs7 => self.buffer.len().saturating_sub(1) / block_size * block_size,
+        };
+        if whole == 0 {
+            return Vec::new();
+        }
+
+        let ciphertext: Vec<u8> = self.buffer.drain(..whole).collect();
+        let mut output = ciphertext.clone();
+        // all blocks are independent before the XOR, so they can go through the cipher together
+        self.cipher.decrypt_blocks(&mut output);
+        xor_in_place(&mut output[..block_size], &self.prev);
+        xor_in_place(&mut output[block_size..], &ciphertext[..whole - block_size]);
+        self.prev.copy_from_slice(&ciphertext[whole - block_size..]);
+        output
+    }
+
+    /// Decrypts the held-back block and strips the padding.
+    pub fn finalize(mut self) -> Result<Vec<u8>, ModeError> {
+        let block_size = self.cipher.block_size();
+        match self.padding {
+            Padding::None if !self.buffer.is_empty() => Err(ModeError::PartialBlock),
+            Padding::None => Ok(Vec::new()),
+            Padding::Pkcs7 => {
+                if self.buffer.len() != block_size {
+                    return Err(ModeError::PartialBlock);
+                }
+                self.padding = Padding::None;
+                let mut output = self.update(&[]);
+                let len = strip_pkcs7(&output, block_size).ok_or(ModeError::InvalidPadding)?;
+                output.truncate(len);
+                Ok(output)
+            }
+        }
+    }
+}
+
+// Length of `data` without its PKCS#7 padding. The padding bytes are compared without an early exit.
+pub(crate) fn strip_pkcs7(data: &[u8], block_size: usize) -> Option<usize> {
+    let pad = *data.last()? as usize;
+    if pad == 0 || pad > block_size {
+        return None;
+    }
+    let mismatch = data[data.len() - pad..].iter().fold(0u8, |acc, &b| acc | (b ^ pad as u8));
+    (mismatch == 0).then(|| data.len() - pad)
+}
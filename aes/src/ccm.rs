@@ -0,0 +1,124 @@
+//! Counter with CBC-MAC (RFC 3610, SP 800-38C).
+//!
+//! The tag is a CBC-MAC over a header block B0 (flags, nonce, message length), the
+//! length-prefixed associated data and the message, then encrypted with the first counter
+//! block. The message itself is encrypted in CTR mode from counter 1 on. M is the tag length,
+//! L the size of the message-length field, which leaves 15 - L bytes for the nonce.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::ct::ct_eq;
+use crate::ctr::{Ctr, Endianness};
+use crate::error::AuthError;
+
+pub struct Ccm<C: BlockCipher> {
+    cipher: C,
+    tag_len: usize,
+    length_bytes: usize,
+}
+
+impl<C: BlockCipher> Ccm<C> {
+    /// `tag_len` (M) must be even and between 4 and 16, `length_bytes` (L) between 2 and 8.
+    pub fn new(cipher: C, tag_len: usize, length_bytes: usize) -> Self {
+        assert_eq!(cipher.block_size(), 16, "CCM needs a 128-bit block cipher");
+        assert!(matches!(tag_len, 4 | 6 | 8 | 10 | 12 | 14 | 16), "CCM tags are 4, 6, ..., 16 bytes");
+        assert!((2..=8).contains(&length_bytes), "CCM length field is 2 to 8 bytes");
+        Self { cipher, tag_len, length_bytes }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Nonce length in bytes, 15 - L.
+    pub fn nonce_len(&self) -> usize {
+        15 - self.length_bytes
+    }
+
+    /// Encrypts `plaintext` and authenticates it together with `aad`.
+    /// Returns the ciphertext followed by the encrypted tag.
+    pub fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        assert!(self.fits_length_field(plaintext.len()), "message too long for a {}-byte length field", self.length_bytes);
+        let tag = self.cbc_mac(nonce, aad, plaintext);
+
+        let mut sealed = self.keystream(nonce).update(plaintext);
+        sealed.extend_from_slice(&self.encrypt_tag(nonce, &tag));
+        sealed
+    }
+
+    /// Decrypts `sealed` and checks its tag. The plaintext is only returned if the tag matches;
+    /// a nonce of the wrong length fails like any other mismatch.
+    pub fn open(&self, nonce: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, AuthError> {
+        if nonce.len() != self.nonce_len() || sealed.len() < self.tag_len || !self.fits_length_field(sealed.len() - self.tag_len) {
+            return Err(AuthError);
+        }
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - self.tag_len);
+
+        // CCM authenticates the plaintext, so it has to be decrypted before it can be checked
+        let plaintext = self.keystream(nonce).update(ciphertext);
+        let expected = self.encrypt_tag(nonce, &self.cbc_mac(nonce, aad, &plaintext));
+        if !ct_eq(&expected, tag) {
+            return Err(AuthError);
+        }
+        Ok(plaintext)
+    }
+
+    fn fits_length_field(&self, len: usize) -> bool {
+        self.length_bytes >= 8 || (len as u64) < 1 << (8 * self.length_bytes)
+    }
+
+    // Flags byte (L - 1), the nonce and a counter of L bytes
+    fn counter_block(&self, nonce: &[u8], counter: u64) -> [u8; 16] {
+        assert_eq!(nonce.len(), self.nonce_len(), "CCM nonce must be 15 - L bytes");
+        let mut block = [0u8; 16];
+        block[0] = (self.length_bytes - 1) as u8;
+        block[1..16 - self.length_bytes].copy_from_slice(nonce);
+        block[16 - self.length_bytes..].copy_from_slice(&counter.to_be_bytes()[8 - self.length_bytes..]);
+        block
+    }
+
+    fn keystream(&self, nonce: &[u8]) -> Ctr<&C> {
+        Ctr::new(&self.cipher, &self.counter_block(nonce, 1), self.length_bytes, Endianness::Big)
+    }
+
+    // U = T xor first M bytes of E(A0)
+    fn encrypt_tag(&self, nonce: &[u8], tag: &[u8]) -> Vec<u8> {
+        let mut s0 = self.counter_block(nonce, 0);
+        self.cipher.encrypt_block(&mut s0);
+        xor_in_place(&mut s0, tag);
+        s0[..self.tag_len].to_vec()
+    }
+
+    fn cbc_mac(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        // B0 is a counter block whose counter is the message length, with Adata and M' in the flags
+        let mut b0 = self.counter_block(nonce, plaintext.len() as u64);
+        b0[0] |= ((!aad.is_empty() as u8) << 6) | (((self.tag_len - 2) / 2) << 3) as u8;
+
+        let mut input = b0.to_vec();
+        if !aad.is_empty() {
+            input.extend_from_slice(&encode_aad_length(aad.len()));
+            input.extend_from_slice(aad);
+            input.resize(input.len().next_multiple_of(16), 0);
+        }
+        input.extend_from_slice(plaintext);
+        input.resize(input.len().next_multiple_of(16), 0);
+
+        let mut x = [0u8; 16];
+        for block in input.chunks_exact(16) {
+            xor_in_place(&mut x, block);
+            self.cipher.encrypt_block(&mut x);
+        }
+        x[..self.tag_len].to_vec()
+    }
+}
+
+// RFC 3610 §2.2: 2 bytes below 2^16 - 2^8, otherwise a 0xfffe or 0xffff marker and 4 or 8 bytes
+fn encode_aad_length(len: usize) -> Vec<u8> {
+    let len = len as u64;
+    if len < 0xff00 {
+        (len as u16).to_be_bytes().to_vec()
+    } else if len <= u32::MAX as u64 {
+        [&[0xff, 0xfe][..], &(len as u32).to_be_bytes()].concat()
+    } else {
+        [&[0xff, 0xff][..], &len.to_be_bytes()].concat()
+    }
+}
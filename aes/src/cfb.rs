@@ -0,0 +1,101 @@
+//! Cipher feedback (SP 800-38A §6.3), with 8-bit or full-block segments.
+//!
+//! Each segment is XORed with the leading bytes of E(register), then the register shifts left
+//! by one segment and takes in the ciphertext segment. Input is handled byte by byte, so chunk
+//! boundaries never have to line up with segments, and a final short segment is allowed.
+
+use crate::block_cipher::BlockCipher;
+
+/// Size of the feedback segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfbSegment {
+    /// CFB-8: one cipher call per byte.
+    Bits8,
+    /// CFB-128 for AES: one cipher call per block.
+    FullBlock,
+}
+
+// Shared state of both directions; they differ only in which side of the XOR is fed back
+struct Cfb<C: BlockCipher> {
+    cipher: C,
+    register: Vec<u8>,
+    keystream: Vec<u8>,
+    segment: Vec<u8>,
+    segment_bytes: usize,
+}
+
+impl<C: BlockCipher> Cfb<C> {
+    fn new(cipher: C, iv: &[u8], segment: CfbSegment) -> Self {
+        let block_size = cipher.block_size();
+        assert_eq!(iv.len(), block_size, "IV must be one block long");
+        let segment_bytes = match segment {
+            CfbSegment::Bits8 => 1,
+            CfbSegment::FullBlock => block_size,
+        };
+        Self {
+            cipher,
+            register: iv.to_vec(),
+            keystream: vec![0u8; block_size],
+            segment: Vec::with_capacity(segment_bytes),
+            segment_bytes,
+        }
+    }
+
+    fn process(&mut self, input: &[u8], decrypt: bool) -> Vec<u8> {
+        let mut output = Vec::with_capacity(input.len());
+        for &byte in input {
+            if self.segment.is_empty() {
+                self.keystream.copy_from_slice(&self.register);
+                self.cipher.encrypt_block(&mut self.keystream);
+            }
+
+            let out = byte ^ self.keystream[self.segment.len()];
+            self.segment.push(if decrypt { byte } else { out });
+            output.push(out);
+
+            if self.segment.len() == self.segment_bytes {
+                self.register.rotate_left(self.segment_bytes);
+                let start = self.register.len() - self.segment_bytes;
+                self.register[start..].copy_from_slice(&self.segment);
+                self.segment.clear();
+            }
+        }
+        output
+    }
+}
+
+pub struct CfbEncryptor<C: BlockCipher>(Cfb<C>);
+
+impl<C: BlockCipher> CfbEncryptor<C> {
+    /// `iv` must be exactly one block long.
+    pub fn new(cipher: C, iv: &[u8], segment: CfbSegment) -> Self {
+        Self(Cfb::new(cipher, iv, segment))
+    }
+
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        self.0.process(input, false)
+    }
+
+    /// Ends the stream. CFB never buffers input, so there is nothing left to emit.
+    pub fn finalize(self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+pub struct CfbDecryptor<C: BlockCipher>(Cfb<C>);
+
+impl<C: BlockCipher> CfbDecryptor<C> {
+    /// `iv` must be exactly one block long.
+    pub fn new(cipher: C, iv: &[u8], segment: CfbSegment) -> Self {
+        Self(Cfb::new(cipher, iv, segment))
+    }
+
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        self.0.process(input, true)
+    }
+
+    /// Ends the stream. CFB never buffers input, so there is nothing left to emit.
+    pub fn finalize(self) -> Vec<u8> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,375 @@
+//! Command-line front end shared by the `aes` and `aes-v2` binaries.
+//!
+//! The binaries only choose the block cipher backend; argument parsing, the modes, the
+//! encodings and the exit codes live here so both tools behave the same.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use crate::block_cipher::{Backend, BlockCipher};
+use crate::cbc::{strip_pkcs7, CbcDecryptor, CbcEncryptor, Padding};
+use crate::ctr::{Ctr, Endianness};
+use crate::ecb::{ecb_decrypt, ecb_encrypt};
+use crate::encoding::{from_base64, from_hex, to_base64, to_hex};
+use crate::error::ModeError;
+use crate::gcm::Gcm;
+use crate::pipeline::ecb_encrypt_stream;
+
+/// The `--help` text for the tool installed as `program`, with `options` (lines formatted like
+/// the option list) describing the flags its binary adds to choose a backend.
+pub fn usage(program: &str, options: &str) -> String {
+    format!(
+        "\
+usage: {program} (encrypt | decrypt) (--key HEX | --key-file PATH) [options]
+       {program} --kattis [128 | 192 | 256] [--threads N]
+
+Reads stdin and writes stdout.
+
+options:
+  -m, --mode MODE        ecb, cbc (default), ctr or gcm
+  -k, --key HEX          16, 24 or 32-byte key in hex
+      --key-file PATH    key as raw bytes or hex text
+      --iv HEX           IV (CBC, CTR) or nonce (GCM); by default a random one is generated
+                         and written in front of the ciphertext, and decrypt reads it from there
+      --aad HEX          GCM associated data
+      --no-padding       ECB/CBC without PKCS#7 padding; the input must be whole blocks
+      --in FORMAT        input encoding: raw (default), hex or base64
+      --out FORMAT       output encoding: raw (default), hex or base64
+{options}  -h, --help             show this message
+
+--kattis, which is also what runs without arguments, reads a raw key (128 bits unless
+another size is given) followed by the plaintext and ECB-encrypts every whole block, on N
+worker threads if --threads is given.
+
+exit status: 0 on success, 1 if the input is rejected (bad encoding, wrong length, bad
+padding, failed authentication), 2 on a usage error, 3 on an I/O error."
+    )
+}
+
+/// Why the tool stopped. Each kind maps to its own exit status.
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad arguments: unknown flag, missing key, key or IV of the wrong length.
+    Usage(String),
+    /// The data was rejected: malformed encoding, wrong length, padding or tag mismatch.
+    Input(String),
+    Io(io::Error),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Input(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::Io(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Input(message) => write!(f, "{}", message),
+            CliError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+impl From<ModeError> for CliError {
+    fn from(err: ModeError) -> Self {
+        CliError::Input(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Ecb,
+    Cbc,
+    Ctr,
+    Gcm,
+}
+
+impl Mode {
+    // length of a generated IV or nonce
+    fn iv_len(self) -> usize {
+        match self {
+            Mode::Ecb => 0,
+            Mode::Cbc | Mode::Ctr => 16,
+            Mode::Gcm => 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Raw,
+    Hex,
+    Base64,
+}
+
+struct Options {
+    encrypt: bool,
+    mode: Mode,
+    key: Vec<u8>,
+    iv: Option<Vec<u8>>,
+    aad: Vec<u8>,
+    padding: Padding,
+    input: Format,
+    output: Format,
+}
+
+/// Runs the tool installed as `program` on `args` (without the program name) and the
+/// process's stdin and stdout, printing any error to stderr. `-h` or `--help` anywhere in
+/// `args` prints the `--help` text instead, with `options` as for `usage`.
+pub fn main(program: &str, options: &str, args: &[String], backend: Backend<'_>) -> ExitCode {
+    let result = if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        writeln!(io::stdout(), "{}", usage(program, options)).map_err(CliError::from)
+    } else {
+        run(args, backend, &mut io::stdin().lock(), &mut io::stdout().lock())
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}: {}", program, err);
+            if let CliError::Usage(_) = err {
+                eprintln!("try '{} --help'", program);
+            }
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+/// Runs the tool on `args` (without the program name), reading `input` and writing `output`.
+/// `--help` is left to `main`, which knows the program name and its options.
+pub fn run(args: &[String], backend: Backend<'_>, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), CliError> {
+    match args.first().map(String::as_str) {
+        None => kattis(&[], backend, input, output),
+        Some("--kattis") => kattis(&args[1..], backend, input, output),
+        Some(_) => {
+            let options = parse(args)?;
+            let cipher = backend(&options.key)
+                .ok_or_else(|| CliError::Usage(format!("unsupported key length of {} bytes", options.key.len())))?;
+
+            let mut data = Vec::new();
+            input.read_to_end(&mut data)?;
+            let data = decode(options.input, &data)?;
+
+            let result = if options.encrypt { encrypt(&options, &*cipher, &data)? } else { decrypt(&options, &*cipher, &data)? };
+            output.write_all(&encode(options.output, &result))?;
+            Ok(output.flush()?)
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Options, CliError> {
+    let usage = |message: String| CliError::Usage(message);
+    let encrypt = match args[0].as_str() {
+        "encrypt" => true,
+        "decrypt" => false,
+        other => return Err(usage(format!("unknown command '{}'", other))),
+    };
+
+    let mut mode = Mode::Cbc;
+    let mut key = None;
+    let mut iv = None;
+    let mut aad = Vec::new();
+    let mut padding = Padding::Pkcs7;
+    let mut input = Format::Raw;
+    let mut output = Format::Raw;
+
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        let mut value = || rest.next().ok_or_else(|| usage(format!("{} needs a value", flag)));
+        match flag.as_str() {
+            "-m" | "--mode" => {
+                mode = match value()?.as_str() {
+                    "ecb" => Mode::Ecb,
+                    "cbc" => Mode::Cbc,
+                    "ctr" => Mode::Ctr,
+                    "gcm" => Mode::Gcm,
+                    other => return Err(usage(format!("unknown mode '{}'", other))),
+                }
+            }
+            "-k" | "--key" => key = Some(hex_arg("--key", value()?)?),
+            "--key-file" => key = Some(read_key_file(value()?)?),
+            "--iv" => iv = Some(hex_arg("--iv", value()?)?),
+            "--aad" => aad = hex_arg("--aad", value()?)?,
+            "--no-padding" => padding = Padding::None,
+            "--in" => input = format_arg(value()?)?,
+            "--out" => output = format_arg(value()?)?,
+            other => return Err(usage(format!("unknown option '{}'", other))),
+        }
+    }
+
+    let key = key.ok_or_else(|| usage("a key is required (--key or --key-file)".to_string()))?;
+    match (mode, &iv) {
+        (Mode::Ecb, Some(_)) => return Err(usage("ECB takes no IV".to_string())),
+        (Mode::Cbc | Mode::Ctr, Some(iv)) if iv.len() != 16 => return Err(usage("the IV must be 16 bytes".to_string())),
+        (Mode::Gcm, Some(iv)) if iv.is_empty() => return Err(usage("the GCM nonce must not be empty".to_string())),
+        _ => {}
+    }
+    if mode != Mode::Gcm && !aad.is_empty() {
+        return Err(usage("--aad is only used by GCM".to_string()));
+    }
+    if padding == Padding::None && !matches!(mode, Mode::Ecb | Mode::Cbc) {
+        return Err(usage("--no-padding only applies to ECB and CBC".to_string()));
+    }
+
+    Ok(Options { encrypt, mode, key, iv, aad, padding, input, output })
+}
+
+fn hex_arg(flag: &str, value: &str) -> Result<Vec<u8>, CliError> {
+    from_hex(value).ok_or_else(|| CliError::Usage(format!("{} is not valid hex", flag)))
+}
+
+fn format_arg(value: &str) -> Result<Format, CliError> {
+    match value {
+        "raw" => Ok(Format::Raw),
+        "hex" => Ok(Format::Hex),
+        "base64" => Ok(Format::Base64),
+        other => Err(CliError::Usage(format!("unknown format '{}', expected raw, hex or base64", other))),
+    }
+}
+
+// A key file holds either hex text or the raw key bytes
+fn read_key_file(path: &str) -> Result<Vec<u8>, CliError> {
+    let contents = fs::read(path).map_err(|err| CliError::Usage(format!("cannot read key file {}: {}", path, err)))?;
+    Ok(std::str::from_utf8(&contents).ok().and_then(from_hex).filter(|key| !key.is_empty()).unwrap_or(contents))
+}
+
+fn decode(format: Format, data: &[u8]) -> Result<Vec<u8>, CliError> {
+    let text = || std::str::from_utf8(data).map_err(|_| CliError::Input("input is not text".to_string()));
+    match format {
+        Format::Raw => Ok(data.to_vec()),
+        Format::Hex => from_hex(text()?).ok_or_else(|| CliError::Input("input is not valid hex".to_string())),
+        Format::Base64 => from_base64(text()?).ok_or_else(|| CliError::Input("input is not valid base64".to_string())),
+    }
+}
+
+fn encode(format: Format, data: &[u8]) -> Vec<u8> {
+    match format {
+        Format::Raw => data.to_vec(),
+        Format::Hex => format!("{}\n", to_hex(data)).into_bytes(),
+        Format::Base64 => format!("{}\n", to_base64(data)).into_bytes(),
+    }
+}
+
+fn encrypt(options: &Options, cipher: &dyn BlockCipher, plaintext: &[u8]) -> Result<Vec<u8>, CliError> {
+    // a generated IV goes in front of the ciphertext, one given on the command line does not
+    let (iv, mut output) = match &options.iv {
+        Some(iv) => (iv.clone(), Vec::new()),
+        None => {
+            let iv = random_bytes(options.mode.iv_len())?;
+            (iv.clone(), iv)
+        }
+    };
+
+    match options.mode {
+        Mode::Ecb => {
+            let mut padded = plaintext.to_vec();
+            if options.padding == Padding::Pkcs7 {
+                let pad = 16 - plaintext.len() % 16;
+                padded.resize(plaintext.len() + pad, pad as u8);
+            } else if !plaintext.len().is_multiple_of(16) {
+                return Err(ModeError::PartialBlock.into());
+            }
+            output.extend_from_slice(&ecb_encrypt(cipher, &padded));
+        }
+        Mode::Cbc => {
+            let mut cbc = CbcEncryptor::new(cipher, &iv, options.padding);
+            output.extend_from_slice(&cbc.update(plaintext));
+            output.extend_from_slice(&cbc.finalize()?);
+        }
+        // the whole IV is the counter block, incremented as a 128-bit big-endian number
+        Mode::Ctr => output.extend_from_slice(&Ctr::new(cipher, &iv, 16, Endianness::Big).update(plaintext)),
+        Mode::Gcm => output.extend_from_slice(&Gcm::new(cipher).seal(&iv, &options.aad, plaintext)),
+    }
+    Ok(output)
+}
+
+fn decrypt(options: &Options, cipher: &dyn BlockCipher, input: &[u8]) -> Result<Vec<u8>, CliError> {
+    let (iv, ciphertext) = match &options.iv {
+        Some(iv) => (iv.as_slice(), input),
+        None if input.len() < options.mode.iv_len() => {
+            return Err(CliError::Input("input is too short to contain the IV".to_string()));
+        }
+        None => input.split_at(options.mode.iv_len()),
+    };
+
+    match options.mode {
+        Mode::Ecb => {
+            if !ciphertext.len().is_multiple_of(16) || (options.padding == Padding::Pkcs7 && ciphertext.is_empty()) {
+                return Err(ModeError::PartialBlock.into());
+            }
+            let mut plaintext = ecb_decrypt(cipher, ciphertext);
+            if options.padding == Padding::Pkcs7 {
+                let len = strip_pkcs7(&plaintext, 16).ok_or(ModeError::InvalidPadding)?;
+                plaintext.truncate(len);
+            }
+            Ok(plaintext)
+        }
+        Mode::Cbc => {
+            let mut cbc = CbcDecryptor::new(cipher, iv, options.padding);
+            let mut plaintext = cbc.update(ciphertext);
+            plaintext.extend_from_slice(&cbc.finalize()?);
+            Ok(plaintext)
+        }
+        Mode::Ctr => Ok(Ctr::new(cipher, iv, 16, Endianness::Big).update(ciphertext)),
+        Mode::Gcm => Gcm::new(cipher)
+            .open(iv, &options.aad, ciphertext)
+            .map_err(|_| CliError::Input("authentication failed".to_string())),
+    }
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, CliError> {
+    let mut bytes = vec![0u8; len];
+    if len > 0 {
+        File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    }
+    Ok(bytes)
+}
+
+// The Kattis submission: a raw key, then ECB over every whole block of the rest of the input.
+// `args` is an optional key size and `--threads N`.
+fn kattis(args: &[String], backend: Backend<'_>, input: &mut dyn Read, output: &mut dyn Write) -> Result<(), CliError> {
+    let mut bits = "128";
+    let mut threads = 1;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--threads" => {
+                threads = rest
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| CliError::Usage("--threads needs a positive number".to_string()))?;
+            }
+            size => bits = size,
+        }
+    }
+
+    let key_len = match bits {
+        "128" => 16,
+        "192" => 24,
+        "256" => 32,
+        other => return Err(CliError::Usage(format!("unsupported key size {}, expected 128, 192 or 256", other))),
+    };
+    let mut key = vec![0u8; key_len];
+    input.read_exact(&mut key).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => CliError::Input(format!("input ends before the {}-bit key", bits)),
+        _ => CliError::Io(err),
+    })?;
+    let cipher = backend(&key).ok_or_else(|| CliError::Usage(format!("{}-bit keys are not supported", bits)))?;
+
+    Ok(ecb_encrypt_stream(&*cipher, input, output, threads)?)
+}
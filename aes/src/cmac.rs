@@ -0,0 +1,116 @@
+//! CMAC / OMAC1 (SP 800-38B, RFC 4493) and the raw CBC-MAC it is built from.
+//!
+//! CMAC is CBC-MAC with a zero IV, except that the last block is XORed with a subkey before
+//! the final encryption: K1 = dbl(E(0)) when the last block is complete, K2 = dbl(K1) when it
+//! had to be padded with 10*. That tweak is what stops the length-extension forgeries plain
+//! CBC-MAC is open to.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::ct::ct_eq;
+use crate::error::AuthError;
+use crate::gf128::gf128_double;
+
+/// Shortest tag `verify` accepts, the 64 bits SP 800-38B recommends as a minimum.
+pub const MIN_TAG_LEN: usize = 8;
+
+#[derive(Clone)]
+pub struct Cmac<C: BlockCipher> {
+    cipher: C,
+    k1: [u8; 16],
+    k2: [u8; 16],
+    x: [u8; 16],
+    // the most recent 0..=16 bytes, which may turn out to be the last block
+    buffer: Vec<u8>,
+}
+
+impl<C: BlockCipher> Cmac<C> {
+    pub fn new(cipher: C) -> Self {
+        assert_eq!(cipher.block_size(), 16, "CMAC needs a 128-bit block cipher");
+
+        let mut l = [0u8; 16];
+        cipher.encrypt_block(&mut l);
+        let k1 = gf128_double(u128::from_be_bytes(l));
+        let k2 = gf128_double(k1);
+        Self { cipher, k1: k1.to_be_bytes(), k2: k2.to_be_bytes(), x: [0u8; 16], buffer: Vec::with_capacity(16) }
+    }
+
+    /// Absorbs `data`. Can be called any number of times with chunks of any size.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            // a full buffer is only known not to be the last block once more data arrives
+            if self.buffer.len() == 16 {
+                xor_in_place(&mut self.x, &self.buffer);
+                self.cipher.encrypt_block(&mut self.x);
+                self.buffer.clear();
+            }
+            self.buffer.push(byte);
+        }
+    }
+
+    /// The 16-byte tag over everything passed to `update`.
+    pub fn finalize(mut self) -> [u8; 16] {
+        let mut last = [0u8; 16];
+        last[..self.buffer.len()].copy_from_slice(&self.buffer);
+        if self.buffer.len() == 16 {
+            xor_in_place(&mut last, &self.k1);
+        } else {
+            last[self.buffer.len()] = 0x80;
+            xor_in_place(&mut last, &self.k2);
+        }
+
+        xor_in_place(&mut self.x, &last);
+        self.cipher.encrypt_block(&mut self.x);
+        self.x
+    }
+
+    /// Checks `tag`, which may be truncated to between `MIN_TAG_LEN` and 16 bytes,
+    /// in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), AuthError> {
+        if !(MIN_TAG_LEN..=16).contains(&tag.len()) {
+            return Err(AuthError);
+        }
+        let expected = self.finalize();
+        if ct_eq(&expected[..tag.len()], tag) {
+            Ok(())
+        } else {
+            Err(AuthError)
+        }
+    }
+}
+
+/// One-shot CMAC of `message`.
+pub fn cmac<C: BlockCipher + ?Sized>(cipher: &C, message: &[u8]) -> [u8; 16] {
+    let mut mac = Cmac::new(cipher);
+    mac.update(message);
+    mac.finalize()
+}
+
+/// Plain CBC-MAC: the last block of CBC encryption under `iv`. No padding and no subkeys,
+/// so `message` must be a whole number of blocks. Provided for the cryptopals CBC-MAC
+/// forgery exercises; use `Cmac` to actually authenticate data.
+pub fn cbc_mac<C: BlockCipher + ?Sized>(cipher: &C, iv: &[u8], message: &[u8]) -> Vec<u8> {
+    let block_size = cipher.block_size();
+    assert_eq!(iv.len(), block_size, "IV must be one block long");
+    assert!(message.len().is_multiple_of(block_size), "CBC-MAC input must be a multiple of the block size");
+
+    let mut x = iv.to_vec();
+    for block in message.chunks_exact(block_size) {
+        xor_in_place(&mut x, block);
+        cipher.encrypt_block(&mut x);
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AES128;
+
+    #[test]
+    fn rfc4493_subkeys() {
+        let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+        let cmac = Cmac::new(AES128::new(key));
+        assert_eq!(cmac.k1, 0xfbeed618357133667c85e08f7236a8de_u128.to_be_bytes());
+        assert_eq!(cmac.k2, 0xf7ddac306ae266ccf90bc11ee46d513b_u128.to_be_bytes());
+    }
+}
@@ -0,0 +1,26 @@
+//! Constant-time helpers.
+
+/// Compares two byte strings without an early exit on the first difference.
+/// Only the lengths, which are public, may end the comparison early.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    // turn the OR of all differences into 0 or 1 without a data-dependent branch
+    ((diff as u32).wrapping_sub(1) >> 31) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_contents_and_lengths() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"tag", b"tag"));
+        assert!(!ct_eq(b"tag", b"tah"));
+        assert!(!ct_eq(b"tag", b"ta"));
+        assert!(!ct_eq(&[0x80], &[0x00]));
+    }
+}
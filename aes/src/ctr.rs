@@ -0,0 +1,113 @@
+//! Counter mode (SP 800-38A §6.5).
+//!
+//! The counter is the last `counter_bytes` bytes of the counter block, incremented modulo
+//! 2^(8·counter_bytes) in either byte order; the bytes in front of it (the nonce) never change.
+//! SP 800-38A uses a big-endian counter over the whole block, cryptopals an 8-byte nonce
+//! followed by a 64-bit little-endian counter.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+
+/// Byte order of the counter inside the counter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+pub struct Ctr<C: BlockCipher> {
+    cipher: C,
+    counter_block: Vec<u8>,
+    counter_bytes: usize,
+    endianness: Endianness,
+    keystream: Vec<u8>,
+    used: usize,
+}
+
+impl<C: BlockCipher> Ctr<C> {
+    /// `initial_block` is the first counter block (nonce followed by the starting counter value).
+    /// `counter_bytes` must be between 1 and the block size.
+    pub fn new(cipher: C, initial_block: &[u8], counter_bytes: usize, endianness: Endianness) -> Self {
+        let block_size = cipher.block_size();
+        assert_eq!(initial_block.len(), block_size, "counter block must be one block long");
+        assert!((1..=block_size).contains(&counter_bytes), "counter must fit in the block");
+        Self {
+            cipher,
+            counter_block: initial_block.to_vec(),
+            counter_bytes,
+            endianness,
+            keystream: Vec::new(),
+            used: 0,
+        }
+    }
+
+    /// Encrypts or decrypts `input`, continuing the keystream where the last call stopped.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        let block_size = self.cipher.block_size();
+        let mut output = input.to_vec();
+
+        // finish the keystream block left over from the previous call
+        let leftover = (self.keystream.len() - self.used).min(output.len());
+        xor_in_place(&mut output[..leftover], &self.keystream[self.used..]);
+        self.used += leftover;
+
+        let rest = &mut output[leftover..];
+        if !rest.is_empty() {
+            // counter blocks for the rest go through the cipher together
+            let blocks = rest.len().div_ceil(block_size);
+            self.keystream.clear();
+            for _ in 0..blocks {
+                self.keystream.extend_from_slice(&self.counter_block);
+                self.increment();
+            }
+            self.cipher.encrypt_blocks(&mut self.keystream);
+            xor_in_place(rest, &self.keystream);
+            self.used = rest.len();
+        }
+        output
+    }
+
+    /// Ends the stream. CTR never buffers input, so there is nothing left to emit.
+    pub fn finalize(self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Moves a fresh stream `blocks` counter values ahead, so that it starts part-way through
+    /// the keystream. Used to let workers encrypt different parts of one message.
+    pub(crate) fn skip_blocks(&mut self, blocks: u64) {
+        assert_eq!(self.used, self.keystream.len(), "can only skip from a block boundary");
+        let start = self.counter_block.len() - self.counter_bytes;
+        let counter = &mut self.counter_block[start..];
+        let len = counter.len();
+        // schoolbook addition, carrying the rest of `blocks` into the more significant bytes
+        let mut carry = blocks as u128;
+        for i in 0..len {
+            if carry == 0 {
+                break;
+            }
+            let byte = match self.endianness {
+                Endianness::Big => &mut counter[len - 1 - i],
+                Endianness::Little => &mut counter[i],
+            };
+            let sum = *byte as u128 + (carry & 0xff);
+            *byte = sum as u8;
+            carry = (carry >> 8) + (sum >> 8);
+        }
+    }
+
+    fn increment(&mut self) {
+        let start = self.counter_block.len() - self.counter_bytes;
+        let counter = &mut self.counter_block[start..];
+        let len = counter.len();
+        // the carry runs from the least significant byte upwards
+        for i in 0..len {
+            let byte = match self.endianness {
+                Endianness::Big => &mut counter[len - 1 - i],
+                Endianness::Little => &mut counter[i],
+            };
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+    }
+}
@@ -1,27 +1,58 @@
-use crate::{AES128, State};
+use crate::{BlockCipher, AES128, AES192, AES256};
+
+// Copies the whole blocks of `input`, a trailing partial block is dropped
+pub(crate) fn full_blocks(input: &[u8], block_size: usize) -> Vec<u8> {
+    input[..input.len() - input.len() % block_size].to_vec()
+}
+
+/// Encrypt plaintext in ECB mode with any block cipher.
+/// `plaintext.len()` must be a multiple of the block size.
+pub fn ecb_encrypt<C: BlockCipher + ?Sized>(cipher: &C, plaintext: &[u8]) -> Vec<u8> {
+    let mut ciphertext = full_blocks(plaintext, cipher.block_size());
+    cipher.encrypt_blocks(&mut ciphertext);
+    ciphertext
+}
+
+/// Decrypt ciphertext in ECB mode with any block cipher.
+/// `ciphertext.len()` must be a multiple of the block size.
+pub fn ecb_decrypt<C: BlockCipher + ?Sized>(cipher: &C, ciphertext: &[u8]) -> Vec<u8> {
+    let mut plaintext = full_blocks(ciphertext, cipher.block_size());
+    cipher.decrypt_blocks(&mut plaintext);
+    plaintext
+}
 
 /// Decrypt ciphertext with AES-128 in ECB mode.
 /// `ciphertext.len()` must be a multiple of 16.
 pub fn aes_128_ecb_decrypt(ciphertext: &[u8], key: &[u8; 16]) -> Vec<u8> {
-    let aes = AES128::new(*key);
-    let mut plaintext = Vec::with_capacity(ciphertext.len());
-    for block in ciphertext.chunks_exact(16) {
-        let mut state = State::new(block.try_into().unwrap());
-        aes.inv_cipher(&mut state);
-        plaintext.extend_from_slice(state.as_bytes());
-    }
-    plaintext
+    ecb_decrypt(&AES128::new(*key), ciphertext)
 }
 
 /// Encrypt plaintext with AES-128 in ECB mode.
 /// `plaintext.len()` must be a multiple of 16.
 pub fn aes_128_ecb_encrypt(plaintext: &[u8], key: &[u8; 16]) -> Vec<u8> {
-    let aes = AES128::new(*key);
-    let mut ciphertext = Vec::with_capacity(plaintext.len());
-    for block in plaintext.chunks_exact(16) {
-        let mut state = State::new(block.try_into().unwrap());
-        aes.cipher(&mut state);
-        ciphertext.extend_from_slice(state.as_bytes());
-    }
-    ciphertext
+    ecb_encrypt(&AES128::new(*key), plaintext)
+}
+
+/// Decrypt ciphertext with AES-192 in ECB mode.
+/// `ciphertext.len()` must be a multiple of 16.
+pub fn aes_192_ecb_decrypt(ciphertext: &[u8], key: &[u8; 24]) -> Vec<u8> {
+    ecb_decrypt(&AES192::new(*key), ciphertext)
+}
+
+/// Encrypt plaintext with AES-192 in ECB mode.
+/// `plaintext.len()` must be a multiple of 16.
+pub fn aes_192_ecb_encrypt(plaintext: &[u8], key: &[u8; 24]) -> Vec<u8> {
+    ecb_encrypt(&AES192::new(*key), plaintext)
+}
+
+/// Decrypt ciphertext with AES-256 in ECB mode.
+/// `ciphertext.len()` must be a multiple of 16.
+pub fn aes_256_ecb_decrypt(ciphertext: &[u8], key: &[u8; 32]) -> Vec<u8> {
+    ecb_decrypt(&AES256::new(*key), ciphertext)
+}
+
+/// Encrypt plaintext with AES-256 in ECB mode.
+/// `plaintext.len()` must be a multiple of 16.
+pub fn aes_256_ecb_encrypt(plaintext: &[u8], key: &[u8; 32]) -> Vec<u8> {
+    ecb_encrypt(&AES256::new(*key), plaintext)
 }
@@ -0,0 +1,52 @@
+//! Error types returned by the modes of operation, the authenticated modes and key wrap.
+
+use std::fmt;
+
+/// Why a mode could not finish processing its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeError {
+    /// The input ended part-way through a block and the mode has no padding to fill it.
+    PartialBlock,
+    /// The decrypted padding is malformed.
+    InvalidPadding,
+    /// An XTS data unit, or the sector size, is shorter than one block.
+    ShortDataUnit,
+}
+
+impl fmt::Display for ModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModeError::PartialBlock => write!(f, "input is not a multiple of the block size"),
+            ModeError::InvalidPadding => write!(f, "invalid padding"),
+            ModeError::ShortDataUnit => write!(f, "XTS data units must be at least one block long"),
+        }
+    }
+}
+
+impl std::error::Error for ModeError {}
+
+/// The tag did not match: the ciphertext, associated data, nonce or key is wrong.
+/// Deliberately carries no detail about where the mismatch was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthError;
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "authentication failed")
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Unwrapping failed its integrity check: the wrapped key was modified, truncated, or
+/// wrapped under a different key-encryption key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityError;
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key unwrap integrity check failed")
+    }
+}
+
+impl std::error::Error for IntegrityError {}
@@ -0,0 +1,45 @@
+//! Fault injection for differential fault analysis experiments.
+//!
+//! `AES128::cipher_with_fault` (and the AES-192/256 versions) run the portable cipher with
+//! one byte of the state flipped part-way through, as a glitch or laser shot on a real device
+//! would. Comparing the result with the correct ciphertext is what `cryptanalysis::dfa` needs.
+
+use crate::alg::{add_round_key, mix_columns, shift_rows, sub_bytes};
+use crate::state::State;
+
+/// A single-byte fault: `mask` is XORed into state byte `position` (column-major, 0 to 15)
+/// at the start of round `round` (1 to Nr), before its SubBytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fault {
+    pub round: usize,
+    pub position: usize,
+    pub mask: u8,
+}
+
+impl Fault {
+    pub fn new(round: usize, position: usize, mask: u8) -> Self {
+        assert!(position < 16, "the AES state has 16 bytes");
+        assert_ne!(mask, 0, "a zero mask is not a fault");
+        Self { round, position, mask }
+    }
+}
+
+// `alg::encrypt_state` with `fault` injected, always on the portable path since AES-NI
+// cannot reach the state between its instructions
+pub(crate) fn encrypt_state_with_fault(state: &mut State, key_schedule: &[State], fault: Fault) {
+    let rounds = key_schedule.len() - 1;
+    assert!((1..=rounds).contains(&fault.round), "AES with {} rounds has no round {}", rounds, fault.round);
+
+    add_round_key(state, &key_schedule[0]);
+    for (round, round_key) in key_schedule.iter().enumerate().skip(1) {
+        if round == fault.round {
+            state[(fault.position % 4, fault.position / 4)] ^= fault.mask;
+        }
+        sub_bytes(state);
+        shift_rows(state);
+        if round < rounds {
+            mix_columns(state);
+        }
+        add_round_key(state, round_key);
+    }
+}
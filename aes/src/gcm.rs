@@ -0,0 +1,111 @@
+//! Galois/Counter Mode (SP 800-38D).
+//!
+//! Confidentiality comes from CTR with a 32-bit big-endian counter, authenticity from GHASH
+//! over the associated data and the ciphertext, keyed with H = E(0^128). Works with any
+//! `BlockCipher` that has 16-byte blocks.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::ct::ct_eq;
+use crate::ctr::{Ctr, Endianness};
+use crate::error::AuthError;
+use crate::gf128::gf128_mul;
+
+pub struct Gcm<C: BlockCipher> {
+    cipher: C,
+    h: u128,
+    tag_len: usize,
+}
+
+impl<C: BlockCipher> Gcm<C> {
+    /// GCM with full 16-byte tags.
+    pub fn new(cipher: C) -> Self {
+        Self::with_tag_len(cipher, 16)
+    }
+
+    /// GCM with tags truncated to `tag_len` bytes: 4, 8 or 12 to 16, the lengths SP 800-38D allows.
+    pub fn with_tag_len(cipher: C, tag_len: usize) -> Self {
+        assert_eq!(cipher.block_size(), 16, "GCM needs a 128-bit block cipher");
+        assert!(matches!(tag_len, 4 | 8 | 12..=16), "GCM tags are 4, 8 or 12 to 16 bytes");
+
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+        Self { cipher, h: u128::from_be_bytes(h), tag_len }
+    }
+
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Encrypts `plaintext` and authenticates it together with `aad`.
+    /// Returns the ciphertext followed by the tag. `iv` may have any non-zero length,
+    /// but 12 bytes is the fast and recommended case.
+    pub fn seal(&self, iv: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let j0 = self.pre_counter_block(iv);
+        let mut sealed = self.keystream(&j0).update(plaintext);
+        let tag = self.tag(&j0, aad, &sealed);
+        sealed.extend_from_slice(&tag);
+        sealed
+    }
+
+    /// Verifies the tag at the end of `sealed` and only then decrypts.
+    /// Any mismatch (ciphertext, tag, `aad`, `iv` or key) gives the same `AuthError`,
+    /// and so does an empty `iv`, which nothing can have been sealed under.
+    pub fn open(&self, iv: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, AuthError> {
+        if iv.is_empty() || sealed.len() < self.tag_len {
+            return Err(AuthError);
+        }
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - self.tag_len);
+
+        let j0 = self.pre_counter_block(iv);
+        if !ct_eq(&self.tag(&j0, aad, ciphertext), tag) {
+            return Err(AuthError);
+        }
+        Ok(self.keystream(&j0).update(ciphertext))
+    }
+
+    // J0: IV || 0^31 || 1 for 96-bit IVs, GHASH(IV || 0-pad || [len(IV)]_128) otherwise
+    fn pre_counter_block(&self, iv: &[u8]) -> [u8; 16] {
+        assert!(!iv.is_empty(), "GCM IV must not be empty");
+        if iv.len() == 12 {
+            let mut j0 = [0u8; 16];
+            j0[..12].copy_from_slice(iv);
+            j0[15] = 1;
+            j0
+        } else {
+            let mut y = 0u128;
+            self.ghash_update(&mut y, iv);
+            y = gf128_mul(y ^ (iv.len() as u128 * 8), self.h);
+            y.to_be_bytes()
+        }
+    }
+
+    // Data is encrypted from inc32(J0) on, J0 itself is kept for the tag
+    fn keystream(&self, j0: &[u8; 16]) -> Ctr<&C> {
+        let mut counter = *j0;
+        let inc32 = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]).wrapping_add(1);
+        counter[12..].copy_from_slice(&inc32.to_be_bytes());
+        Ctr::new(&self.cipher, &counter, 4, Endianness::Big)
+    }
+
+    fn tag(&self, j0: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+        let mut y = 0u128;
+        self.ghash_update(&mut y, aad);
+        self.ghash_update(&mut y, ciphertext);
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        y = gf128_mul(y ^ lengths, self.h);
+
+        let mut tag = *j0;
+        self.cipher.encrypt_block(&mut tag);
+        xor_in_place(&mut tag, &y.to_be_bytes());
+        tag[..self.tag_len].to_vec()
+    }
+
+    // Absorbs `data` into the GHASH accumulator, zero-padding the last block
+    fn ghash_update(&self, y: &mut u128, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            *y = gf128_mul(*y ^ u128::from_be_bytes(block), self.h);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! GF(2^128) arithmetic for the AES-based modes.
+
+/// GF(2^128) multiplication in GCM's bit order (SP 800-38D, Algorithm 1).
+///
+/// Blocks are read as big-endian integers, but bit 127 is the coefficient of x^0, so the
+/// reduction polynomial x^128 + x^7 + x^2 + x + 1 becomes `0xe1 << 120` and "times x" is a
+/// right shift. Both the conditional add and the conditional reduce are masks, not branches.
+pub const fn gf128_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xe1 << 120;
+
+    let mut z = 0u128;
+    let mut v = y;
+    let mut i = 0;
+    while i < 128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & bit.wrapping_neg();
+        v = (v >> 1) ^ (R & (v & 1).wrapping_neg());
+        i += 1;
+    }
+    z
+}
+
+/// Doubling in GF(2^128) with the conventional bit order (SP 800-38B, RFC 5297 `dbl`):
+/// the block is a big-endian integer, shifted left, with x^128 folded back in as 0x87.
+/// The same role `xtime` plays for GF(2^8).
+pub const fn gf128_double(x: u128) -> u128 {
+    (x << 1) ^ (0x87 & (x >> 127).wrapping_neg())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_is_the_top_bit() {
+        let x = 0x66e94bd4ef8a2c3b884cfa59ca342b2e;
+        assert_eq!(gf128_mul(x, 1 << 127), x);
+        assert_eq!(gf128_mul(1 << 127, x), x);
+    }
+
+    #[test]
+    fn x_times_x127_reduces() {
+        // x is bit 126, x^127 is bit 0: the product x^128 reduces to x^7 + x^2 + x + 1
+        assert_eq!(gf128_mul(1 << 126, 1), 0xe1 << 120);
+    }
+
+    #[test]
+    fn double_reduces_on_carry() {
+        assert_eq!(gf128_double(1), 2);
+        assert_eq!(gf128_double(1 << 127), 0x87);
+    }
+}
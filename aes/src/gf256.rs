@@ -1,6 +1,6 @@
 
 #[inline(always)]
-pub fn gf256_mul(a: u8, b: u8) -> u8 {
+pub const fn gf256_mul(a: u8, b: u8) -> u8 {
     // GF(2^8) multiplication
     let mut result = 0u8;
     let mut a = a;
@@ -20,7 +20,7 @@ pub fn gf256_mul(a: u8, b: u8) -> u8 {
 }
 
 #[inline(always)]
-pub fn xtime(x: u8) -> u8 {
+pub const fn xtime(x: u8) -> u8 {
     if x & 0x80 != 0 {
         (x << 1) ^ 0x1B // x^8 + x^4 + x^3 + x + 1
     } else {
@@ -28,10 +28,10 @@ pub fn xtime(x: u8) -> u8 {
     }
 }
 
-pub fn gf256_mul2(x: u8) -> u8 {
+pub const fn gf256_mul2(x: u8) -> u8 {
     xtime(x)
 }
 
-pub fn gf256_mul3(x: u8) -> u8 {
+pub const fn gf256_mul3(x: u8) -> u8 {
     x ^ xtime(x) // 3 = 2 + 1
 }
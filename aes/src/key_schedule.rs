@@ -0,0 +1,56 @@
+//! Round keys and key-schedule inversion.
+//!
+//! `expand_key` runs the FIPS-197 KeyExpansion forward and returns every round key. The
+//! schedule is also invertible: each word is w[i] = w[i - Nk] ^ g(w[i - 1]), so any Nk
+//! consecutive words give w[i - Nk] and, step by step, the cipher key. That is what a
+//! side-channel, fault or integral attack needs once it has recovered a round key. For
+//! AES-128 one round key is Nk = 4 words; AES-192 and AES-256 need the round key plus the
+//! first half or all of the next one.
+
+use crate::alg::{key_expansion, rcon, rot_word, sub_word};
+use crate::state::State;
+
+/// All Nr + 1 round keys of a 16, 24 or 32-byte AES key, round key 0 first.
+pub fn expand_key(key: &[u8]) -> Vec<State> {
+    match key.len() {
+        16 => key_expansion::<11>(key).to_vec(),
+        24 => key_expansion::<13>(key).to_vec(),
+        32 => key_expansion::<15>(key).to_vec(),
+        _ => panic!("Key must be 16, 24 or 32 bytes"),
+    }
+}
+
+/// Recovers the cipher key from the schedule bytes starting at round key `round`. Pass as
+/// many bytes as the key is long: round key `round` for AES-128, followed by the first 8
+/// bytes of round key `round + 1` for AES-192, or all of it for AES-256.
+pub fn invert_key_schedule(round_key_bytes: &[u8], round: usize) -> Vec<u8> {
+    assert!(matches!(round_key_bytes.len(), 16 | 24 | 32), "Key must be 16, 24 or 32 bytes");
+    let nk = round_key_bytes.len() / 4;
+    let first_word = 4 * round;
+    // the words must all lie in the 4 * (Nr + 1) = 4 * (Nk + 7) words of the schedule
+    assert!(first_word + nk <= 4 * (nk + 7), "AES-{} has no {} words from round key {}", nk * 32, nk, round);
+
+    // words[i] is w[i]: the known words sit at the top and each step fills in the one Nk below
+    let mut words = vec![[0u8; 4]; nk + first_word];
+    for (j, chunk) in round_key_bytes.chunks_exact(4).enumerate() {
+        words[first_word + j].copy_from_slice(chunk);
+    }
+    for i in (nk..first_word + nk).rev() {
+        let mut temp = words[i - 1];
+        if i % nk == 0 {
+            temp = sub_word(rot_word(temp));
+            temp[0] ^= rcon(i / nk)[0];
+        } else if nk > 6 && i % nk == 4 {
+            temp = sub_word(temp);
+        }
+        for j in 0..4 {
+            words[i - nk][j] = words[i][j] ^ temp[j];
+        }
+    }
+    words[..nk].concat()
+}
+
+/// `invert_key_schedule` for AES-128, where one round key is enough.
+pub fn aes128_key_from_round_key(round_key: &[u8; 16], round: usize) -> [u8; 16] {
+    invert_key_schedule(round_key, round).try_into().unwrap()
+}
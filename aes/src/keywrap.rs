@@ -0,0 +1,133 @@
+//! AES key wrap: KW (RFC 3394) and KW with padding, KWP (RFC 5649).
+//!
+//! The key data is split into 64-bit semiblocks R[1..n] and mixed with a 64-bit integrity
+//! register A over 6n block-cipher calls. Unwrapping runs the steps backwards and accepts
+//! the result only if A comes back as the expected initial value. KWP puts the data length
+//! into that initial value, so any length from 1 byte up can be wrapped.
+
+use crate::block_cipher::BlockCipher;
+use crate::ct::ct_eq;
+use crate::error::IntegrityError;
+
+/// Initial value of A for KW (RFC 3394 §2.2.3.1).
+const KW_IV: [u8; 8] = [0xa6; 8];
+/// Constant half of the KWP alternative initial value, followed by the 32-bit data length.
+const KWP_ICV: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// Wraps `key_data`, which must be at least 16 bytes and a multiple of 8, under `kek`.
+/// The result is 8 bytes longer than the input.
+pub fn kw_wrap<C: BlockCipher + ?Sized>(kek: &C, key_data: &[u8]) -> Vec<u8> {
+    assert!(key_data.len() >= 16 && key_data.len().is_multiple_of(8), "KW key data must be at least 16 bytes and a multiple of 8");
+    wrap_with_iv(kek, KW_IV, key_data)
+}
+
+/// Unwraps the output of `kw_wrap`.
+pub fn kw_unwrap<C: BlockCipher + ?Sized>(kek: &C, wrapped: &[u8]) -> Result<Vec<u8>, IntegrityError> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(IntegrityError);
+    }
+    let (a, key_data) = unwrap_raw(kek, wrapped);
+    if !ct_eq(&a, &KW_IV) {
+        return Err(IntegrityError);
+    }
+    Ok(key_data)
+}
+
+/// Wraps `key_data` of any non-zero length under `kek`, zero-padding it to a multiple of 8.
+pub fn kwp_wrap<C: BlockCipher + ?Sized>(kek: &C, key_data: &[u8]) -> Vec<u8> {
+    assert!(!key_data.is_empty(), "KWP key data must not be empty");
+    let len = u32::try_from(key_data.len()).expect("KWP key data is limited to 2^32 - 1 bytes");
+
+    let mut aiv = [0u8; 8];
+    aiv[..4].copy_from_slice(&KWP_ICV);
+    aiv[4..].copy_from_slice(&len.to_be_bytes());
+
+    let mut padded = key_data.to_vec();
+    padded.resize(key_data.len().next_multiple_of(8), 0);
+
+    if padded.len() == 8 {
+        // a single semiblock is encrypted together with the AIV as one block
+        let mut block = [aiv.to_vec(), padded].concat();
+        check_block_size(kek);
+        kek.encrypt_block(&mut block);
+        block
+    } else {
+        wrap_with_iv(kek, aiv, &padded)
+    }
+}
+
+/// Unwraps the output of `kwp_wrap`, checking the length indicator and the zero padding.
+pub fn kwp_unwrap<C: BlockCipher + ?Sized>(kek: &C, wrapped: &[u8]) -> Result<Vec<u8>, IntegrityError> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(8) {
+        return Err(IntegrityError);
+    }
+    let (a, mut padded) = if wrapped.len() == 16 {
+        let mut block = wrapped.to_vec();
+        check_block_size(kek);
+        kek.decrypt_block(&mut block);
+        let padded = block.split_off(8);
+        (block.try_into().unwrap(), padded)
+    } else {
+        unwrap_raw(kek, wrapped)
+    };
+
+    // RFC 5649 §3: 8(n - 1) < MLI <= 8n and the padding is all zeros
+    let len = u32::from_be_bytes(a[4..].try_into().unwrap()) as usize;
+    let length_ok = len <= padded.len() && len + 8 > padded.len();
+    let padding_ok = length_ok && padded[len..].iter().all(|&b| b == 0);
+    if !(ct_eq(&a[..4], &KWP_ICV) & length_ok & padding_ok) {
+        return Err(IntegrityError);
+    }
+    padded.truncate(len);
+    Ok(padded)
+}
+
+fn check_block_size<C: BlockCipher + ?Sized>(kek: &C) {
+    assert_eq!(kek.block_size(), 16, "key wrap needs a 128-bit block cipher");
+}
+
+// W(S) from RFC 3394 §2.2.1 with the given initial value; the output is A || R[1] || ... || R[n]
+fn wrap_with_iv<C: BlockCipher + ?Sized>(kek: &C, iv: [u8; 8], key_data: &[u8]) -> Vec<u8> {
+    check_block_size(kek);
+    let n = key_data.len() / 8;
+    let mut a = iv;
+    let mut r = key_data.to_vec();
+
+    let mut block = [0u8; 16];
+    for j in 0..6 {
+        for (i, semiblock) in r.chunks_exact_mut(8).enumerate() {
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(semiblock);
+            kek.encrypt_block(&mut block);
+
+            let t = (n * j + i + 1) as u64;
+            a = (u64::from_be_bytes(block[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            semiblock.copy_from_slice(&block[8..]);
+        }
+    }
+
+    [a.to_vec(), r].concat()
+}
+
+// W^-1(C) from RFC 3394 §2.2.2, returning the recovered A and R[1..n] for the caller to check
+fn unwrap_raw<C: BlockCipher + ?Sized>(kek: &C, wrapped: &[u8]) -> ([u8; 8], Vec<u8>) {
+    check_block_size(kek);
+    let n = wrapped.len() / 8 - 1;
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r = wrapped[8..].to_vec();
+
+    let mut block = [0u8; 16];
+    for j in (0..6).rev() {
+        for (i, semiblock) in r.chunks_exact_mut(8).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            block[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            block[8..].copy_from_slice(semiblock);
+            kek.decrypt_block(&mut block);
+
+            a.copy_from_slice(&block[..8]);
+            semiblock.copy_from_slice(&block[8..]);
+        }
+    }
+
+    (a, r)
+}
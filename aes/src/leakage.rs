@@ -0,0 +1,50 @@
+//! Intermediate-value hook for power-analysis experiments.
+//!
+//! `AES128::cipher_with_leakage` (and the AES-192/256 versions) run the portable cipher and
+//! report every step with the state before and after it, which is what a leakage model needs:
+//! Hamming weight of a value, or Hamming distance between a register's old and new contents.
+
+use crate::alg::{add_round_key, mix_columns, shift_rows, sub_bytes};
+use crate::state::State;
+
+/// A round operation of the cipher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    AddRoundKey,
+    SubBytes,
+    ShiftRows,
+    MixColumns,
+    /// Changing the mask of a masked implementation without changing the value underneath;
+    /// the unmasked ciphers never report it.
+    Remask,
+}
+
+/// One step reported by `cipher_with_leakage`. Round 0 is the initial AddRoundKey.
+#[derive(Clone, Copy, Debug)]
+pub struct Step<'a> {
+    pub round: usize,
+    pub operation: Operation,
+    pub before: &'a State,
+    pub after: &'a State,
+}
+
+// `alg::encrypt_state` calling `leak` after every operation, on the portable path since
+// AES-NI keeps the intermediate states inside its instructions
+pub(crate) fn encrypt_state_with_leakage(state: &mut State, key_schedule: &[State], leak: &mut dyn FnMut(Step)) {
+    let rounds = key_schedule.len() - 1;
+    let mut apply = |state: &mut State, round: usize, operation: Operation, f: &dyn Fn(&mut State)| {
+        let before = *state;
+        f(state);
+        leak(Step { round, operation, before: &before, after: state });
+    };
+
+    apply(state, 0, Operation::AddRoundKey, &|s| add_round_key(s, &key_schedule[0]));
+    for (round, round_key) in key_schedule.iter().enumerate().skip(1) {
+        apply(state, round, Operation::SubBytes, &sub_bytes);
+        apply(state, round, Operation::ShiftRows, &shift_rows);
+        if round < rounds {
+            apply(state, round, Operation::MixColumns, &mix_columns);
+        }
+        apply(state, round, Operation::AddRoundKey, &|s| add_round_key(s, round_key));
+    }
+}
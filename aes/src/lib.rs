@@ -1,9 +1,55 @@
 pub mod state;
 pub mod alg;
+pub mod key_schedule;
+pub mod reduced;
+pub mod fault;
+pub mod leakage;
 pub mod gf256;
+pub mod gf128;
 pub mod sbox;
+pub mod block_cipher;
 pub mod ecb;
+pub mod cbc;
+pub mod ctr;
+pub mod cfb;
+pub mod ofb;
+pub mod gcm;
+pub mod ccm;
+pub mod siv;
+pub mod keywrap;
+pub mod xts;
+pub mod cmac;
+pub mod cavp;
+pub mod cli;
+pub mod encoding;
+pub mod parallel;
+pub mod pipeline;
+pub mod ct;
+pub mod error;
+pub mod ni;
+pub mod rng;
+#[cfg(feature = "ttable")]
+pub mod ttable;
 
 pub use state::State;
-pub use alg::AES128;
-pub use ecb::{aes_128_ecb_decrypt, aes_128_ecb_encrypt};
\ No newline at end of file
+pub use alg::{AES128, AES192, AES256};
+pub use reduced::ReducedAES128;
+pub use fault::Fault;
+pub use block_cipher::{aes_for_key, Backend, BlockCipher};
+pub use cbc::{CbcDecryptor, CbcEncryptor, Padding};
+pub use ctr::{Ctr, Endianness};
+pub use cfb::{CfbDecryptor, CfbEncryptor, CfbSegment};
+pub use ofb::Ofb;
+pub use gcm::Gcm;
+pub use ccm::Ccm;
+pub use siv::Siv;
+pub use cmac::{cbc_mac, cmac, Cmac};
+pub use keywrap::{kw_unwrap, kw_wrap, kwp_unwrap, kwp_wrap};
+pub use xts::Xts;
+pub use error::{AuthError, IntegrityError, ModeError};
+#[cfg(feature = "ttable")]
+pub use ttable::TTableAES128;
+pub use ecb::{
+    aes_128_ecb_decrypt, aes_128_ecb_encrypt, aes_192_ecb_decrypt, aes_192_ecb_encrypt,
+    aes_256_ecb_decrypt, aes_256_ecb_encrypt, ecb_decrypt, ecb_encrypt,
+};
\ No newline at end of file
@@ -1,30 +1,7 @@
-use aes::{AES128, State};
-use std::io::{self, Read, Write};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let mut stdin = io::stdin().lock();
-    let mut stdout = io::stdout().lock();
-
-    // Read 16-byte key from stdin
-    let mut key = [0u8; 16];
-    stdin.read_exact(&mut key)?;
-
-    let aes = AES128::new(key);
-    let mut block = [0u8; 16];
-
-    loop {
-        let n = stdin.read(&mut block)?;
-        if n == 0 {
-            break;
-        }
-        if n < 16 {
-            break;
-        }
-
-        let mut state = State::new(block);
-        aes.cipher(&mut state);
-        stdout.write_all(state.as_bytes())?;
-    }
-
-    Ok(())
+// Without arguments this is the Kattis submission; see `aes --help` for the full tool
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    aes::cli::main("aes", "", &args, &aes::aes_for_key)
 }
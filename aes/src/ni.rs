@@ -0,0 +1,176 @@
+//! AES-NI hardware backend.
+//!
+//! `AES128`, `AES192` and `AES256` check `is_x86_feature_detected!("aes")` once when they are
+//! built and route every block through `aesenc`/`aesdec` when it is available. The round keys
+//! are the same bytes the portable path uses: `aesdec` implements the equivalent inverse
+//! cipher, so it consumes the `inv_key_schedule` output directly. On other architectures
+//! `is_available` is always false and the portable code runs.
+
+use crate::state::State;
+
+/// Whether this CPU supports the AES-NI instructions.
+pub fn is_available() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        std::is_x86_feature_detected!("aes")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Encrypts the state with `aesenc`. Must only be called when `is_available()` is true.
+pub(crate) fn encrypt_state(state: &mut State, round_keys: &[State]) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: callers only take this path after `is_available()` reported AES-NI support
+    unsafe {
+        x86::encrypt_state(state, round_keys)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = (state, round_keys);
+        unreachable!("AES-NI is only available on x86_64")
+    }
+}
+
+/// Decrypts the state with `aesdec`, using the equivalent inverse cipher key schedule.
+/// Must only be called when `is_available()` is true.
+pub(crate) fn decrypt_state(state: &mut State, dec_round_keys: &[State]) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: callers only take this path after `is_available()` reported AES-NI support
+    unsafe {
+        x86::decrypt_state(state, dec_round_keys)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = (state, dec_round_keys);
+        unreachable!("AES-NI is only available on x86_64")
+    }
+}
+
+/// AES-128 key expansion with `aeskeygenassist`, returning the encryption and the
+/// equivalent inverse cipher round keys. Must only be called when `is_available()` is true.
+pub(crate) fn key_schedule_128(key: &[u8; 16]) -> ([State; 11], [State; 11]) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: callers only take this path after `is_available()` reported AES-NI support
+    unsafe {
+        x86::key_schedule_128(key)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = key;
+        unreachable!("AES-NI is only available on x86_64")
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use crate::state::State;
+    use std::arch::x86_64::*;
+
+    #[inline]
+    #[target_feature(enable = "aes")]
+    unsafe fn load(state: &State) -> __m128i {
+        _mm_loadu_si128(state.as_bytes().as_ptr() as *const __m128i)
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes")]
+    unsafe fn store(block: __m128i) -> State {
+        let mut bytes = [0u8; 16];
+        _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, block);
+        State::new(bytes)
+    }
+
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn encrypt_state(state: &mut State, round_keys: &[State]) {
+        let rounds = round_keys.len() - 1;
+
+        let mut block = _mm_xor_si128(load(state), load(&round_keys[0]));
+        for round_key in &round_keys[1..rounds] {
+            block = _mm_aesenc_si128(block, load(round_key));
+        }
+        block = _mm_aesenclast_si128(block, load(&round_keys[rounds]));
+
+        *state = store(block);
+    }
+
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn decrypt_state(state: &mut State, dec_round_keys: &[State]) {
+        let rounds = dec_round_keys.len() - 1;
+
+        let mut block = _mm_xor_si128(load(state), load(&dec_round_keys[0]));
+        for round_key in &dec_round_keys[1..rounds] {
+            block = _mm_aesdec_si128(block, load(round_key));
+        }
+        block = _mm_aesdeclast_si128(block, load(&dec_round_keys[rounds]));
+
+        *state = store(block);
+    }
+
+    // w[i] = w[i - 4] ^ temp for the four words of a round key: XOR the key with itself
+    // shifted by one, two and three words, then add the broadcast SubWord(RotWord) ^ rcon word
+    #[inline]
+    #[target_feature(enable = "aes")]
+    unsafe fn next_round_key(key: __m128i, assist: __m128i) -> __m128i {
+        let assist = _mm_shuffle_epi32::<0xff>(assist);
+        let mut key = key;
+        key = _mm_xor_si128(key, _mm_slli_si128::<4>(key));
+        key = _mm_xor_si128(key, _mm_slli_si128::<4>(key));
+        key = _mm_xor_si128(key, _mm_slli_si128::<4>(key));
+        _mm_xor_si128(key, assist)
+    }
+
+    #[target_feature(enable = "aes")]
+    pub(super) unsafe fn key_schedule_128(key: &[u8; 16]) -> ([State; 11], [State; 11]) {
+        // aeskeygenassist takes the round constant as an immediate, hence the unrolled schedule
+        let mut keys = [_mm_setzero_si128(); 11];
+        keys[0] = load(&State::new(*key));
+        keys[1] = next_round_key(keys[0], _mm_aeskeygenassist_si128::<0x01>(keys[0]));
+        keys[2] = next_round_key(keys[1], _mm_aeskeygenassist_si128::<0x02>(keys[1]));
+        keys[3] = next_round_key(keys[2], _mm_aeskeygenassist_si128::<0x04>(keys[2]));
+        keys[4] = next_round_key(keys[3], _mm_aeskeygenassist_si128::<0x08>(keys[3]));
+        keys[5] = next_round_key(keys[4], _mm_aeskeygenassist_si128::<0x10>(keys[4]));
+        keys[6] = next_round_key(keys[5], _mm_aeskeygenassist_si128::<0x20>(keys[5]));
+        keys[7] = next_round_key(keys[6], _mm_aeskeygenassist_si128::<0x40>(keys[6]));
+        keys[8] = next_round_key(keys[7], _mm_aeskeygenassist_si128::<0x80>(keys[7]));
+        keys[9] = next_round_key(keys[8], _mm_aeskeygenassist_si128::<0x1b>(keys[8]));
+        keys[10] = next_round_key(keys[9], _mm_aeskeygenassist_si128::<0x36>(keys[9]));
+
+        let mut round_keys = [State::zero(); 11];
+        let mut dec_round_keys = [State::zero(); 11];
+        for i in 0..11 {
+            round_keys[i] = store(keys[i]);
+            // equivalent inverse cipher: reversed order, aesimc (InvMixColumns) on the middle keys
+            dec_round_keys[10 - i] = if i == 0 || i == 10 {
+                store(keys[i])
+            } else {
+                store(_mm_aesimc_si128(keys[i]))
+            };
+        }
+
+        (round_keys, dec_round_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alg::{inv_key_schedule, key_expansion};
+
+    #[test]
+    fn keygenassist_schedule_matches_portable() {
+        if !is_available() {
+            eprintln!("AES-NI not available, skipping");
+            return;
+        }
+
+        let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+        let expected: [State; 11] = key_expansion(&key);
+
+        let (round_keys, dec_round_keys) = key_schedule_128(&key);
+        assert_eq!(round_keys, expected);
+        assert_eq!(dec_round_keys, inv_key_schedule(&expected));
+    }
+}
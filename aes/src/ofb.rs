@@ -0,0 +1,45 @@
+//! Output feedback (SP 800-38A §6.4).
+//!
+//! The keystream is the IV encrypted over and over, independent of the data, so the same
+//! operation encrypts and decrypts.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+
+pub struct Ofb<C: BlockCipher> {
+    cipher: C,
+    keystream: Vec<u8>,
+    used: usize,
+}
+
+impl<C: BlockCipher> Ofb<C> {
+    /// `iv` must be exactly one block long.
+    pub fn new(cipher: C, iv: &[u8]) -> Self {
+        let block_size = cipher.block_size();
+        assert_eq!(iv.len(), block_size, "IV must be one block long");
+        // marked as used up, so the first byte triggers E(IV)
+        Self { cipher, keystream: iv.to_vec(), used: block_size }
+    }
+
+    /// Encrypts or decrypts `input`, continuing the keystream where the last call stopped.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut output = input.to_vec();
+        let mut rest = output.as_mut_slice();
+        while !rest.is_empty() {
+            if self.used == self.keystream.len() {
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.used = 0;
+            }
+            let take = (self.keystream.len() - self.used).min(rest.len());
+            let (chunk, tail) = rest.split_at_mut(take);
+            xor_in_place(chunk, &self.keystream[self.used..]);
+            self.used += take;
+            rest = tail;
+        }
+        output
+    }
+
+    /// Ends the stream. OFB never buffers input, so there is nothing left to emit.
+    pub fn finalize(self) -> Vec<u8> {
+        Vec::new()
+    }
+}
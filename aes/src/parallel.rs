@@ -0,0 +1,121 @@
+//! Multi-threaded bulk ECB, CTR and CBC decryption.
+//!
+//! In these modes each output block depends only on the key and on input the caller already
+//! has, so the data is cut into one contiguous run of whole blocks per `std::thread` worker.
+//! The output is byte-for-byte the same as the serial functions for any thread count. CBC
+//! encryption chains every block on the one before it and has no parallel version.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::cbc::{strip_pkcs7, Padding};
+use crate::ctr::{Ctr, Endianness};
+use crate::ecb::full_blocks;
+use crate::error::ModeError;
+
+/// The number of threads the OS suggests, 1 if it cannot tell.
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Encrypts whole blocks in place on `threads` workers.
+pub fn encrypt_blocks<C: BlockCipher + Sync + ?Sized>(cipher: &C, blocks: &mut [u8], threads: usize) {
+    for_each_run(blocks, cipher.block_size(), threads, |_, run| cipher.encrypt_blocks(run));
+}
+
+/// Decrypts whole blocks in place on `threads` workers.
+pub fn decrypt_blocks<C: BlockCipher + Sync + ?Sized>(cipher: &C, blocks: &mut [u8], threads: usize) {
+    for_each_run(blocks, cipher.block_size(), threads, |_, run| cipher.decrypt_blocks(run));
+}
+
+/// `ecb::ecb_encrypt` on `threads` workers. A trailing partial block is dropped, as there.
+pub fn ecb_encrypt<C: BlockCipher + Sync + ?Sized>(cipher: &C, plaintext: &[u8], threads: usize) -> Vec<u8> {
+    let mut ciphertext = full_blocks(plaintext, cipher.block_size());
+    encrypt_blocks(cipher, &mut ciphertext, threads);
+    ciphertext
+}
+
+/// `ecb::ecb_decrypt` on `threads` workers.
+pub fn ecb_decrypt<C: BlockCipher + Sync + ?Sized>(cipher: &C, ciphertext: &[u8], threads: usize) -> Vec<u8> {
+    let mut plaintext = full_blocks(ciphertext, cipher.block_size());
+    decrypt_blocks(cipher, &mut plaintext, threads);
+    plaintext
+}
+
+/// CTR encryption or decryption of a whole message on `threads` workers, with the same
+/// arguments as `Ctr::new`. Each worker starts its own counter at the first block of its run.
+pub fn ctr_xor<C: BlockCipher + Sync + ?Sized>(
+    cipher: &C,
+    initial_block: &[u8],
+    counter_bytes: usize,
+    endianness: Endianness,
+    input: &[u8],
+    threads: usize,
+) -> Vec<u8> {
+    let mut output = input.to_vec();
+    for_each_run(&mut output, cipher.block_size(), threads, |first_block, run| {
+        let mut ctr = Ctr::new(cipher, initial_block, counter_bytes, endianness);
+        ctr.skip_blocks(first_block as u64);
+        let processed = ctr.update(run);
+        run.copy_from_slice(&processed);
+    });
+    output
+}
+
+/// CBC decryption of a whole message on `threads` workers, the same as `CbcDecryptor`
+/// followed by `finalize`.
+pub fn cbc_decrypt<C: BlockCipher + Sync + ?Sized>(
+    cipher: &C,
+    iv: &[u8],
+    ciphertext: &[u8],
+    padding: Padding,
+    threads: usize,
+) -> Result<Vec<u8>, ModeError> {
+    let block_size = cipher.block_size();
+    assert_eq!(iv.len(), block_size, "IV must be one block long");
+    if !ciphertext.len().is_multiple_of(block_size) || (padding == Padding::Pkcs7 && ciphertext.is_empty()) {
+        return Err(ModeError::PartialBlock);
+    }
+
+    let mut plaintext = ciphertext.to_vec();
+    for_each_run(&mut plaintext, block_size, threads, |first_block, run| {
+        if run.is_empty() {
+            return;
+        }
+        cipher.decrypt_blocks(run);
+        // every block is XORed with the ciphertext block before it, which is still in `ciphertext`
+        let (start, end) = (first_block * block_size, first_block * block_size + run.len());
+        let prev = if start == 0 { iv } else { &ciphertext[start - block_size..start] };
+        xor_in_place(&mut run[..block_size], prev);
+        xor_in_place(&mut run[block_size..], &ciphertext[start..end - block_size]);
+    });
+
+    if padding == Padding::Pkcs7 {
+        let len = strip_pkcs7(&plaintext, block_size).ok_or(ModeError::InvalidPadding)?;
+        plaintext.truncate(len);
+    }
+    Ok(plaintext)
+}
+
+// Calls `f(first_block, run)` for up to `threads` contiguous runs of `data` covering it
+// entirely. Runs are whole blocks except possibly the last; with one thread, or too little
+// data to split, `f` runs once on the calling thread.
+fn for_each_run<F>(data: &mut [u8], block_size: usize, threads: usize, f: F)
+where
+    F: Fn(usize, &mut [u8]) + Sync,
+{
+    let blocks_per_run = data.len().div_ceil(block_size).div_ceil(threads.max(1)).max(1);
+    let run_len = blocks_per_run * block_size;
+    if threads <= 1 || data.len() <= run_len {
+        f(0, data);
+        return;
+    }
+
+    let f = &f;
+    thread::scope(|scope| {
+        for (i, run) in data.chunks_mut(run_len).enumerate() {
+            scope.spawn(move || f(i * blocks_per_run, run));
+        }
+    });
+}
@@ -0,0 +1,136 @@
+//! Round-reduced AES-128 for cryptanalysis experiments.
+//!
+//! Runs the first `rounds` rounds of AES-128 with the standard key schedule, so round key i
+//! is the same as in the full cipher. Like the full cipher, the last round leaves out
+//! MixColumns; `with_final_mix_columns` keeps it, which makes no difference to security
+//! because the attacker can undo that linear layer on the ciphertext.
+
+use crate::alg::{
+    add_round_key, inv_mix_columns, inv_shift_rows, inv_sub_bytes, key_expansion, mix_columns, shift_rows, sub_bytes,
+};
+use crate::block_cipher::{with_state, BlockCipher};
+use crate::ni;
+use crate::state::State;
+
+pub struct ReducedAES128 {
+    // the first rounds + 1 keys of the AES-128 schedule
+    round_keys: Vec<State>,
+    final_mix_columns: bool,
+    aes_ni: bool,
+}
+
+impl ReducedAES128 {
+    /// AES-128 cut to `rounds` rounds (1 to 10), the last one without MixColumns.
+    pub fn new(key: [u8; 16], rounds: usize) -> Self {
+        Self::build(key, rounds, false)
+    }
+
+    /// AES-128 cut to `rounds` rounds (1 to 10), every round including MixColumns.
+    pub fn with_final_mix_columns(key: [u8; 16], rounds: usize) -> Self {
+        Self::build(key, rounds, true)
+    }
+
+    fn build(key: [u8; 16], rounds: usize, final_mix_columns: bool) -> Self {
+        assert!((1..=10).contains(&rounds), "AES-128 has 1 to 10 rounds");
+        let round_keys = key_expansion::<11>(&key)[..=rounds].to_vec();
+        // `aesenclast` is exactly a round without MixColumns
+        let aes_ni = ni::is_available() && !final_mix_columns;
+        Self { round_keys, final_mix_columns, aes_ni }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    pub fn has_final_mix_columns(&self) -> bool {
+        self.final_mix_columns
+    }
+
+    pub fn cipher(&self, state: &mut State) {
+        if self.aes_ni {
+            ni::encrypt_state(state, &self.round_keys);
+            return;
+        }
+        let rounds = self.rounds();
+        add_round_key(state, &self.round_keys[0]);
+        for (round, round_key) in self.round_keys.iter().enumerate().skip(1) {
+            sub_bytes(state);
+            shift_rows(state);
+            if round < rounds || self.final_mix_columns {
+                mix_columns(state);
+            }
+            add_round_key(state, round_key);
+        }
+    }
+
+    /// Inverse of `cipher`, running the rounds backwards (not the equivalent inverse cipher).
+    pub fn inv_cipher(&self, state: &mut State) {
+        let rounds = self.rounds();
+        for round in (1..=rounds).rev() {
+            add_round_key(state, &self.round_keys[round]);
+            if round < rounds || self.final_mix_columns {
+                inv_mix_columns(state);
+            }
+            inv_shift_rows(state);
+            inv_sub_bytes(state);
+        }
+        add_round_key(state, &self.round_keys[0]);
+    }
+}
+
+impl BlockCipher for ReducedAES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.cipher(state));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.inv_cipher(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AES128;
+
+    #[test]
+    fn ten_rounds_is_aes128() {
+        let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+        let mut full = [0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07, 0x34];
+        let mut reduced = full;
+        AES128::new(key).encrypt_block(&mut full);
+        ReducedAES128::new(key, 10).encrypt_block(&mut reduced);
+        assert_eq!(full, reduced);
+    }
+
+    #[test]
+    fn every_round_count_inverts() {
+        let key = [0x5a; 16];
+        for rounds in 1..=10 {
+            for cipher in [ReducedAES128::new(key, rounds), ReducedAES128::with_final_mix_columns(key, rounds)] {
+                let mut block: [u8; 16] = std::array::from_fn(|i| i as u8);
+                cipher.encrypt_block(&mut block);
+                cipher.decrypt_block(&mut block);
+                assert_eq!(block, std::array::from_fn(|i| i as u8), "{} rounds", rounds);
+            }
+        }
+    }
+
+    #[test]
+    fn aes_ni_matches_portable() {
+        let key = [0x3c; 16];
+        for rounds in 1..=10 {
+            let mut cipher = ReducedAES128::new(key, rounds);
+            let mut fast = [0x42u8; 16];
+            cipher.encrypt_block(&mut fast);
+            cipher.aes_ni = false;
+            let mut slow = [0x42u8; 16];
+            cipher.encrypt_block(&mut slow);
+            assert_eq!(fast, slow, "{} rounds", rounds);
+        }
+    }
+}
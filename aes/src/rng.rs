@@ -0,0 +1,37 @@
+//! The seeded generator behind the random keys, blocks and masks of the tests, simulations and
+//! attacks here and in `aes-v2` and `cryptanalysis`.
+//!
+//! xorshift64* (Vigna): fast, reproducible from a `u64` seed and statistically good enough for
+//! test inputs, masks, noise and class orders. It is not a cryptographic generator.
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // spread the seed over the state, which must never be zero
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// The top byte of the next output, the best mixed one.
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        std::array::from_fn(|_| self.next_u8())
+    }
+
+    /// A standard normal sample (Box-Muller, one of the pair).
+    pub fn gaussian(&mut self) -> f64 {
+        let u1 = ((self.next_u64() >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        let u2 = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+}
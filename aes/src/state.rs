@@ -60,8 +60,8 @@ impl State {
     #[inline]
     pub fn set_col(&mut self, col: usize, values: [u8; 4]) {
         assert!(col < 4, "Column must be between 0 and 3");
-        for row in 0..4 {
-            self.set(row, col, values[row]);
+        for (row, value) in values.into_iter().enumerate() {
+            self.set(row, col, value);
         }
     }
 }
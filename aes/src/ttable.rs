@@ -0,0 +1,256 @@
+//! 32-bit T-table AES backend.
+//!
+//! SubBytes, ShiftRows and MixColumns of one round are folded into four 256-entry u32 tables,
+//! so a full round is 16 table lookups and XORs. Output is identical to `AES128`.
+//!
+//! The lookups are indexed by secret state bytes, which is exactly what cache-timing attacks
+//! exploit, so this backend also serves as the realistic victim for the side-channel work.
+
+use crate::alg::{inv_key_schedule, key_expansion};
+use crate::block_cipher::{with_state, BlockCipher};
+use crate::gf256::{gf256_mul, gf256_mul2, gf256_mul3};
+use crate::sbox::{AES_INV_SBOX, AES_SBOX};
+use crate::state::State;
+
+// Te0[x] = column (02·S[x], 01·S[x], 01·S[x], 03·S[x]), big-endian
+const fn build_te0() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let s = AES_SBOX[i];
+        table[i] = u32::from_be_bytes([gf256_mul2(s), s, s, gf256_mul3(s)]);
+        i += 1;
+    }
+    table
+}
+
+// Td0[x] = column (0e·S⁻¹[x], 09·S⁻¹[x], 0d·S⁻¹[x], 0b·S⁻¹[x]), big-endian
+const fn build_td0() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let s = AES_INV_SBOX[i];
+        table[i] = u32::from_be_bytes([gf256_mul(s, 0x0e), gf256_mul(s, 0x09), gf256_mul(s, 0x0d), gf256_mul(s, 0x0b)]);
+        i += 1;
+    }
+    table
+}
+
+// Te1..Te3 (and Td1..Td3) are the same columns rotated one byte further each time
+const fn rotate_table(table: [u32; 256], bits: u32) -> [u32; 256] {
+    let mut rotated = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        rotated[i] = table[i].rotate_right(bits);
+        i += 1;
+    }
+    rotated
+}
+
+pub const TE0: [u32; 256] = build_te0();
+pub const TE1: [u32; 256] = rotate_table(TE0, 8);
+pub const TE2: [u32; 256] = rotate_table(TE0, 16);
+pub const TE3: [u32; 256] = rotate_table(TE0, 24);
+
+pub const TD0: [u32; 256] = build_td0();
+pub const TD1: [u32; 256] = rotate_table(TD0, 8);
+pub const TD2: [u32; 256] = rotate_table(TD0, 16);
+pub const TD3: [u32; 256] = rotate_table(TD0, 24);
+
+/// One table read by `TTableAES128::cipher_traced`. `table` is 0 to 3 for Te0 to Te3 and 4
+/// for the S-box of the last round; `round` runs from 1 to 10.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lookup {
+    pub round: usize,
+    pub table: usize,
+    pub index: u8,
+}
+
+pub struct TTableAES128 {
+    round_keys: [u32; 44],
+    dec_round_keys: [u32; 44],
+}
+
+impl TTableAES128 {
+    pub fn new(key: [u8; 16]) -> Self {
+        let round_keys: [State; 11] = key_expansion(&key);
+        let dec_round_keys = inv_key_schedule(&round_keys);
+        Self {
+            round_keys: schedule_words(&round_keys),
+            dec_round_keys: schedule_words(&dec_round_keys),
+        }
+    }
+
+    pub fn cipher(&self, state: &mut State) {
+        self.encrypt(state, |_| {});
+    }
+
+    /// `cipher`, calling `trace` with every table entry it reads, in order.
+    pub fn cipher_traced(&self, state: &mut State, trace: &mut dyn FnMut(Lookup)) {
+        self.encrypt(state, trace);
+    }
+
+    // `cipher` with a hook on every lookup, which the compiler drops again for `cipher`
+    fn encrypt(&self, state: &mut State, mut on_lookup: impl FnMut(Lookup)) {
+        let rk = &self.round_keys;
+        let mut s = load_words(state);
+        for (word, key) in s.iter_mut().zip(&rk[0..4]) {
+            *word ^= key;
+        }
+        let mut index = |round, table, byte: u32| {
+            on_lookup(Lookup { round, table, index: byte as u8 });
+            byte as usize & 0xff
+        };
+
+        // First 9 rounds: one Te lookup per byte, ShiftRows is folded into which column each byte is read from
+        for (round, round_key) in (1..).zip(rk[4..40].chunks_exact(4)) {
+            let mut t = [0u32; 4];
+            for c in 0..4 {
+                t[c] = TE0[index(round, 0, s[c] >> 24)]
+                    ^ TE1[index(round, 1, (s[(c + 1) % 4] >> 16) & 0xff)]
+                    ^ TE2[index(round, 2, (s[(c + 2) % 4] >> 8) & 0xff)]
+                    ^ TE3[index(round, 3, s[(c + 3) % 4] & 0xff)]
+                    ^ round_key[c];
+            }
+            s = t;
+        }
+
+        // Last round has no MixColumns, so it goes through the plain S-box
+        let mut out = [0u8; 16];
+        for c in 0..4 {
+            let word = u32::from_be_bytes([
+                AES_SBOX[index(10, 4, s[c] >> 24)],
+                AES_SBOX[index(10, 4, (s[(c + 1) % 4] >> 16) & 0xff)],
+                AES_SBOX[index(10, 4, (s[(c + 2) % 4] >> 8) & 0xff)],
+                AES_SBOX[index(10, 4, s[(c + 3) % 4] & 0xff)],
+            ]) ^ rk[40 + c];
+            out[c * 4..c * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        *state = State::new(out);
+    }
+
+    /// Inverse cipher (decryption). Decrypts the state in place using the equivalent inverse cipher.
+    pub fn inv_cipher(&self, state: &mut State) {
+        let dk = &self.dec_round_keys;
+        let mut s = load_words(state);
+        for (word, key) in s.iter_mut().zip(&dk[0..4]) {
+            *word ^= key;
+        }
+
+        // InvShiftRows reads row r from column c - r instead of c + r
+        for round_key in dk[4..40].chunks_exact(4) {
+            let mut t = [0u32; 4];
+            for c in 0..4 {
+                t[c] = TD0[(s[c] >> 24) as usize]
+                    ^ TD1[((s[(c + 3) % 4] >> 16) & 0xff) as usize]
+                    ^ TD2[((s[(c + 2) % 4] >> 8) & 0xff) as usize]
+                    ^ TD3[(s[(c + 1) % 4] & 0xff) as usize]
+                    ^ round_key[c];
+            }
+            s = t;
+        }
+
+        let mut out = [0u8; 16];
+        for c in 0..4 {
+            let word = u32::from_be_bytes([
+                AES_INV_SBOX[(s[c] >> 24) as usize],
+                AES_INV_SBOX[((s[(c + 3) % 4] >> 16) & 0xff) as usize],
+                AES_INV_SBOX[((s[(c + 2) % 4] >> 8) & 0xff) as usize],
+                AES_INV_SBOX[(s[(c + 1) % 4] & 0xff) as usize],
+            ]) ^ dk[40 + c];
+            out[c * 4..c * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        *state = State::new(out);
+    }
+}
+
+impl BlockCipher for TTableAES128 {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.cipher(state));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        with_state(block, |state| self.inv_cipher(state));
+    }
+}
+
+// Each state column becomes one big-endian word
+fn load_words(state: &State) -> [u32; 4] {
+    let bytes = state.as_bytes();
+    let mut words = [0u32; 4];
+    for (c, word) in words.iter_mut().enumerate() {
+        *word = u32::from_be_bytes(bytes[c * 4..c * 4 + 4].try_into().unwrap());
+    }
+    words
+}
+
+fn schedule_words<const N: usize, const W: usize>(round_keys: &[State; N]) -> [u32; W] {
+    let mut words = [0u32; W];
+    for (chunk, round_key) in words.chunks_exact_mut(4).zip(round_keys) {
+        chunk.copy_from_slice(&load_words(round_key));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::AES128;
+
+    #[test]
+    fn tables_are_rotations_of_each_other() {
+        // S[0x00] = 0x63, 02·63 = c6, 03·63 = a5
+        assert_eq!(TE0[0], 0xc66363a5);
+        assert_eq!(TE1[0], 0xa5c66363);
+        assert_eq!(TE3[0x53], TE0[0x53].rotate_left(8));
+        assert_eq!(TD2[0x7f], TD0[0x7f].rotate_right(16));
+    }
+
+    #[test]
+    fn matches_byte_oriented_cipher() {
+        let mut rng = Rng::new(0x9e3779b97f4a7c15);
+
+        for _ in 0..64 {
+            let key = rng.bytes();
+            let reference = AES128::new(key);
+            let ttable = TTableAES128::new(key);
+
+            let input = State::new(rng.bytes());
+            let mut expected = input;
+            let mut actual = input;
+            reference.cipher(&mut expected);
+            ttable.cipher(&mut actual);
+            assert_eq!(actual, expected, "T-table cipher must match AES128::cipher");
+
+            ttable.inv_cipher(&mut actual);
+            assert_eq!(actual, input, "T-table inv_cipher must undo cipher");
+        }
+    }
+
+    #[test]
+    fn traced_cipher_reports_every_lookup() {
+        let key = [0x2b; 16];
+        let ttable = TTableAES128::new(key);
+        let input = State::new(std::array::from_fn(|i| i as u8 * 17));
+        let mut expected = input;
+        ttable.cipher(&mut expected);
+
+        let mut lookups = Vec::new();
+        let mut actual = input;
+        ttable.cipher_traced(&mut actual, &mut |lookup| lookups.push(lookup));
+        assert_eq!(actual, expected);
+        assert_eq!(lookups.len(), 160);
+        assert!(lookups[144..].iter().all(|l| l.round == 10 && l.table == 4));
+
+        // round 1 reads byte (row r, column c) of plaintext ^ key from Te_r
+        for lookup in &lookups[..16] {
+            let position = input.as_bytes().iter().zip(key).position(|(p, k)| p ^ k == lookup.index).unwrap();
+            assert_eq!((lookup.round, lookup.table), (1, position % 4));
+        }
+    }
+}
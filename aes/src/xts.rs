@@ -0,0 +1,124 @@
+//! XTS-AES (IEEE 1619) for sector-based storage encryption.
+//!
+//! Every data unit (sector) gets its own tweak T = E_K2(data unit number), and block j of the
+//! unit is encrypted as E_K1(P ^ T·α^j) ^ T·α^j. The same plaintext therefore encrypts
+//! differently in every block position and every sector, without storing an IV. A data unit
+//! that is not a multiple of 16 bytes is handled with ciphertext stealing, so the ciphertext
+//! is exactly as long as the plaintext.
+
+use crate::block_cipher::{xor_in_place, BlockCipher};
+use crate::error::ModeError;
+use crate::gf128::gf128_double;
+
+pub struct Xts<C: BlockCipher> {
+    data_cipher: C,
+    tweak_cipher: C,
+}
+
+impl<C: BlockCipher> Xts<C> {
+    /// `data_cipher` is keyed with Key1 and `tweak_cipher` with Key2, the two halves of the
+    /// XTS key. A 256-bit XTS-AES key is therefore two AES-128 keys.
+    pub fn new(data_cipher: C, tweak_cipher: C) -> Self {
+        assert_eq!(data_cipher.block_size(), 16, "XTS needs a 128-bit block cipher");
+        assert_eq!(tweak_cipher.block_size(), 16, "XTS needs a 128-bit block cipher");
+        Self { data_cipher, tweak_cipher }
+    }
+
+    /// Encrypts one data unit. `plaintext` must be at least one block long.
+    pub fn encrypt_data_unit(&self, data_unit: u128, plaintext: &[u8]) -> Vec<u8> {
+        self.process(data_unit, plaintext, false)
+    }
+
+    /// Decrypts one data unit. `ciphertext` must be at least one block long.
+    pub fn decrypt_data_unit(&self, data_unit: u128, ciphertext: &[u8]) -> Vec<u8> {
+        self.process(data_unit, ciphertext, true)
+    }
+
+    /// Encrypts consecutive sectors of `sector_size` bytes, numbered from `first_data_unit`.
+    /// Only the last sector may be shorter, and like every sector it needs at least one block.
+    pub fn encrypt_sectors(
+        &self,
+        first_data_unit: u128,
+        sector_size: usize,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, ModeError> {
+        check_sectors(sector_size, plaintext.len())?;
+        Ok((first_data_unit..)
+            .zip(plaintext.chunks(sector_size))
+            .flat_map(|(data_unit, sector)| self.encrypt_data_unit(data_unit, sector))
+            .collect())
+    }
+
+    /// Inverse of `encrypt_sectors`.
+    pub fn decrypt_sectors(
+        &self,
+        first_data_unit: u128,
+        sector_size: usize,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, ModeError> {
+        check_sectors(sector_size, ciphertext.len())?;
+        Ok((first_data_unit..)
+            .zip(ciphertext.chunks(sector_size))
+            .flat_map(|(data_unit, sector)| self.decrypt_data_unit(data_unit, sector))
+            .collect())
+    }
+
+    fn process(&self, data_unit: u128, input: &[u8], decrypt: bool) -> Vec<u8> {
+        assert!(input.len() >= 16, "XTS data units are at least one block long");
+
+        // the data unit number goes in little-endian, and α-multiplication works on the
+        // little-endian reading of the tweak as well
+        let mut tweak = data_unit.to_le_bytes();
+        self.tweak_cipher.encrypt_block(&mut tweak);
+        let mut t = u128::from_le_bytes(tweak);
+
+        let tail = input.len() % 16;
+        // with stealing, the last full block and the tail are handled together below
+        let plain_blocks = if tail == 0 { input.len() / 16 } else { input.len() / 16 - 1 };
+
+        let mut output = input.to_vec();
+        for block in output[..plain_blocks * 16].chunks_exact_mut(16) {
+            self.block(block, t, decrypt);
+            t = gf128_double(t);
+        }
+        if tail == 0 {
+            return output;
+        }
+
+        // Ciphertext stealing (IEEE 1619 §5.3.2): the last full block is processed with the
+        // tweak after it when decrypting, so the two tweaks swap between the directions
+        let (t_full, t_last) = if decrypt { (gf128_double(t), t) } else { (t, gf128_double(t)) };
+        let start = plain_blocks * 16;
+        let (full, partial) = output[start..].split_at_mut(16);
+
+        self.block(full, t_full, decrypt);
+        // the tail takes the first bytes of the processed block, which is completed with the tail input
+        let mut last = [0u8; 16];
+        last[..tail].copy_from_slice(partial);
+        last[tail..].copy_from_slice(&full[tail..]);
+        partial.copy_from_slice(&full[..tail]);
+
+        self.block(&mut last, t_last, decrypt);
+        full.copy_from_slice(&last);
+        output
+    }
+
+    fn block(&self, block: &mut [u8], t: u128, decrypt: bool) {
+        let t = t.to_le_bytes();
+        xor_in_place(block, &t);
+        if decrypt {
+            self.data_cipher.decrypt_block(block);
+        } else {
+            self.data_cipher.encrypt_block(block);
+        }
+        xor_in_place(block, &t);
+    }
+}
+
+// Every sector, the last one included, must be at least one block long
+fn check_sectors(sector_size: usize, len: usize) -> Result<(), ModeError> {
+    if sector_size < 16 || (1..16).contains(&(len % sector_size)) {
+        return Err(ModeError::ShortDataUnit);
+    }
+    Ok(())
+}
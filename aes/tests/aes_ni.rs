@@ -0,0 +1,71 @@
+//! Test: differential checks of the AES-NI backend against the portable software path.
+//! On a CPU without AES-NI both sides are portable and the tests only check the fallback.
+
+use aes::rng::Rng;
+use aes::{State, AES128, AES192, AES256};
+
+#[test]
+fn new_uses_aes_ni_when_detected() {
+    let aes = AES128::new([0u8; 16]);
+    assert_eq!(aes.uses_aes_ni(), aes::ni::is_available());
+    assert!(!AES128::new_portable([0u8; 16]).uses_aes_ni());
+}
+
+#[test]
+fn aes128_matches_portable() {
+    let mut rng = Rng::new(0x0123_4567_89ab_cdef);
+    for _ in 0..256 {
+        let key = rng.bytes::<16>();
+        let fast = AES128::new(key);
+        let portable = AES128::new_portable(key);
+
+        let input = State::new(rng.bytes());
+        let (mut a, mut b) = (input, input);
+        fast.cipher(&mut a);
+        portable.cipher(&mut b);
+        assert_eq!(a, b, "cipher differs for key {:02x?}", key);
+
+        fast.inv_cipher(&mut a);
+        portable.inv_cipher(&mut b);
+        assert_eq!(a, input);
+        assert_eq!(b, input);
+    }
+}
+
+#[test]
+fn aes192_matches_portable() {
+    let mut rng = Rng::new(0xfeed_f00d_dead_beef);
+    for _ in 0..128 {
+        let key = rng.bytes::<24>();
+        let fast = AES192::new(key);
+        let portable = AES192::new_portable(key);
+
+        let input = State::new(rng.bytes());
+        let (mut a, mut b) = (input, input);
+        fast.cipher(&mut a);
+        portable.cipher(&mut b);
+        assert_eq!(a, b, "cipher differs for key {:02x?}", key);
+
+        fast.inv_cipher(&mut a);
+        assert_eq!(a, input);
+    }
+}
+
+#[test]
+fn aes256_matches_portable() {
+    let mut rng = Rng::new(0x1357_9bdf_2468_ace0);
+    for _ in 0..128 {
+        let key = rng.bytes::<32>();
+        let fast = AES256::new(key);
+        let portable = AES256::new_portable(key);
+
+        let input = State::new(rng.bytes());
+        let (mut a, mut b) = (input, input);
+        fast.cipher(&mut a);
+        portable.cipher(&mut b);
+        assert_eq!(a, b, "cipher differs for key {:02x?}", key);
+
+        fast.inv_cipher(&mut a);
+        assert_eq!(a, input);
+    }
+}
@@ -0,0 +1,84 @@
+//! Test: the BlockCipher trait over AES and over a toy cipher with a different block size.
+
+use aes::{ecb_decrypt, ecb_encrypt, BlockCipher, State, AES128, AES256};
+
+// 8-byte toy cipher: XOR with the key, then rotate the block left by three bytes
+struct ToyCipher {
+    key: [u8; 8],
+}
+
+impl BlockCipher for ToyCipher {
+    fn block_size(&self) -> usize {
+        8
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        for (b, k) in block.iter_mut().zip(self.key) {
+            *b ^= k;
+        }
+        block.rotate_left(3);
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        block.rotate_right(3);
+        for (b, k) in block.iter_mut().zip(self.key) {
+            *b ^= k;
+        }
+    }
+}
+
+#[test]
+fn aes_block_matches_state_cipher() {
+    let key = *b"YELLOW SUBMARINE";
+    let aes = AES128::new(key);
+
+    let mut block = *b"sixteen byte msg";
+    aes.encrypt_block(&mut block);
+
+    let mut state = State::new(*b"sixteen byte msg");
+    aes.cipher(&mut state);
+    assert_eq!(&block, state.as_bytes());
+
+    aes.decrypt_block(&mut block);
+    assert_eq!(&block, b"sixteen byte msg");
+}
+
+#[test]
+fn multi_block_variants_match_single_blocks() {
+    let aes = AES256::new([7u8; 32]);
+    let plaintext: Vec<u8> = (0..64).collect();
+
+    let mut bulk = plaintext.clone();
+    aes.encrypt_blocks(&mut bulk);
+
+    for (i, chunk) in plaintext.chunks(16).enumerate() {
+        let mut block = chunk.to_vec();
+        aes.encrypt_block(&mut block);
+        assert_eq!(block, bulk[i * 16..(i + 1) * 16]);
+    }
+
+    aes.decrypt_blocks(&mut bulk);
+    assert_eq!(bulk, plaintext);
+}
+
+#[test]
+fn ecb_works_with_toy_and_boxed_ciphers() {
+    let toy = ToyCipher { key: *b"toy key!" };
+    let plaintext = b"0123456789abcdef";
+
+    let ciphertext = ecb_encrypt(&toy, plaintext);
+    assert_eq!(ciphertext.len(), 16);
+    assert_ne!(&ciphertext[..], &plaintext[..]);
+    assert_eq!(ecb_decrypt(&toy, &ciphertext), plaintext);
+
+    let boxed: Box<dyn BlockCipher> = Box::new(AES128::new([0u8; 16]));
+    assert_eq!(boxed.block_size(), 16);
+    let ciphertext = ecb_encrypt(&boxed, plaintext);
+    assert_eq!(ecb_decrypt(&*boxed, &ciphertext), plaintext);
+}
+
+#[test]
+#[should_panic(expected = "multiple of the block size")]
+fn encrypt_blocks_rejects_partial_blocks() {
+    AES128::new([0u8; 16]).encrypt_blocks(&mut [0u8; 20]);
+}
@@ -0,0 +1,111 @@
+//! Test: every AESAVS known-answer file in tests/vectors against every AES backend, through the
+//! ECB, CBC, OFB, CFB8 and CFB128 modes, and the first published MMT and MCT records for ECB and
+//! CBC. Run with `--nocapture` to see the per-file counts.
+
+use std::path::Path;
+
+use aes::cavp::{run_dir, FileReport, RspFile};
+use aes::{aes_for_key, BlockCipher, AES128, AES192, AES256};
+
+fn vectors() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors"))
+}
+
+// Prints the report and fails on any mismatch or on a backend that ran nothing
+fn check(backend: &str, reports: &[FileReport]) {
+    println!("{}:", backend);
+    for report in reports {
+        println!("  {}", report);
+    }
+    let failures: Vec<String> = reports
+        .iter()
+        .flat_map(|r| r.failures.iter().map(move |f| format!("{} {}", r.file, f)))
+        .collect();
+    assert!(failures.is_empty(), "{}: {} records failed: {:?}", backend, failures.len(), failures);
+    assert!(reports.iter().map(|r| r.passed).sum::<usize>() > 0, "{}: no records ran", backend);
+}
+
+fn portable_backend(key: &[u8]) -> Option<Box<dyn BlockCipher + Send + Sync>> {
+    Some(match key.len() {
+        16 => Box::new(AES128::new_portable(key.try_into().unwrap())),
+        24 => Box::new(AES192::new_portable(key.try_into().unwrap())),
+        32 => Box::new(AES256::new_portable(key.try_into().unwrap())),
+        _ => return None,
+    })
+}
+
+#[test]
+fn default_backend_passes_all_files() {
+    let reports = run_dir(vectors(), &aes_for_key).unwrap();
+    let known_answer = reports.iter().filter(|r| !r.file.contains("MMT") && !r.file.contains("MCT")).count();
+    assert_eq!(known_answer, 60, "expected 5 modes x 3 key sizes x 4 known-answer types");
+    // the MMT and MCT archives come as a whole or not at all
+    assert!(matches!(reports.len() - known_answer, 0 | 30), "expected all 30 MMT and MCT files or none");
+    assert!(reports.iter().all(|r| r.skipped == 0));
+    check("AES (AES-NI when available)", &reports);
+}
+
+#[test]
+fn portable_backend_passes_all_files() {
+    check("AES portable", &run_dir(vectors(), &portable_backend).unwrap());
+}
+
+#[cfg(feature = "ttable")]
+#[test]
+fn ttable_backend_passes_128_bit_files() {
+    let backend = |key: &[u8]| -> Option<Box<dyn BlockCipher + Send + Sync>> {
+        Some(Box::new(aes::TTableAES128::new(key.try_into().ok()?)))
+    };
+    let reports = run_dir(vectors(), &backend).unwrap();
+    check("AES-128 T-table", &reports);
+    // 20 known-answer files, plus 10 MMT and MCT files when those are present
+    assert!(matches!(reports.iter().filter(|r| r.skipped == 0).count(), 20 | 30));
+}
+
+// COUNT = 0 of ECBMMT128, CBCMMT128, ECBMCT128 and CBCMCT128 from NIST's aesmmt.zip and aesmct.zip
+#[test]
+fn published_mmt_and_mct_records() {
+    let records = [
+        ("ECBMMT128.rsp", "KEY = edfdb257cb37cdf182c5455b0c0efebb\n\
+                           PLAINTEXT = 1695fe475421cace3557daca01f445ff\nCIPHERTEXT = 7888beae6e7a426332a7eaa2f808e637\n"),
+        ("CBCMMT128.rsp", "KEY = 1f8e4973953f3fb0bd6b16662e9a3c17\nIV = 2fe2b333ceda8f98f4a99b40d2cd34a8\n\
+                           PLAINTEXT = 45cf12964fc824ab76616ae2f4bf0822\nCIPHERTEXT = 0f61c4d44c5147c03c195ad7e2cc12b2\n"),
+        ("ECBMCT128.rsp", "KEY = 139a35422f1d61de3c91787fe0507afd\n\
+                           PLAINTEXT = b9145a768b7dc489a096b546f43b231f\nCIPHERTEXT = d7c3ffac9031238650901e157364c386\n"),
+        ("CBCMCT128.rsp", "KEY = 8809e7dd3a959ee5d8dbb13f501f2274\nIV = e5c0bb535d7d54572ad06d170a0e58ae\n\
+                           PLAINTEXT = 1fd4ee65603e6130cfc2a82ab3d56c24\nCIPHERTEXT = b127a5b4c4692d87483db0c3b0d11e64\n"),
+    ];
+    for (name, record) in records {
+        let file = RspFile::parse(name, &format!("[ENCRYPT]\n\nCOUNT = 0\n{}", record)).unwrap();
+        check(name, &[file.run(&aes_for_key), file.run(&portable_backend)]);
+    }
+}
+
+#[test]
+fn parses_records_and_sections() {
+    let text = "# comment\n\n[ENCRYPT]\n\nCOUNT = 0\nKEY = 000102030405060708090a0b0c0d0e0f\n\
+                PLAINTEXT = 00112233445566778899aabbccddeeff\nCIPHERTEXT = 69c4e0d86a7b0430d8cdb78070b4c55a\n\n\
+                [DECRYPT]\n\nCOUNT = 0\nKEY = 000102030405060708090a0b0c0d0e0f\n\
+                CIPHERTEXT = 69c4e0d86a7b0430d8cdb78070b4c55a\nPLAINTEXT = 00112233445566778899aabbccddeeff\n";
+    let file = RspFile::parse("ECBGFSbox128.rsp", text).unwrap();
+    assert_eq!(file.cases.len(), 2);
+    assert!(file.cases[0].encrypt && !file.cases[1].encrypt);
+    assert!(!file.monte_carlo);
+
+    let report = file.run(&aes_for_key);
+    assert_eq!((report.passed, report.failed()), (2, 0));
+
+    // a wrong expected value is reported against its record
+    let broken = text.replacen("c55a", "c55b", 1);
+    let report = RspFile::parse("ECBGFSbox128.rsp", &broken).unwrap().run(&aes_for_key);
+    assert_eq!(report.failures, ["ENCRYPT COUNT = 0"]);
+    assert_eq!(report.to_string(), "ECBGFSbox128.rsp: 1 passed, 1 failed");
+}
+
+#[test]
+fn classifies_file_names() {
+    assert!(RspFile::parse("CFB8MCT256.rsp", "").unwrap().monte_carlo);
+    assert!(RspFile::parse("CFB1VarKey128.rsp", "").unwrap().mode.is_none());
+    assert!(RspFile::parse("notes.rsp", "").is_err());
+    assert!(RspFile::parse("ECBMMT128.rsp", "KEY = 00").is_err());
+}
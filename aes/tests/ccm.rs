@@ -0,0 +1,115 @@
+//! Test: AES-CCM against the RFC 3610 packet vectors (#1 to #12) plus non-default M and L.
+
+use aes::{AuthError, Ccm, AES128};
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+// Packets 1-12 share the key C0..CF; the input packet is 00 01 02 ... of which the first
+// `header` bytes are authenticated only
+fn check_packet(tag_len: usize, nonce: &str, total: u8, header: usize, expected_tail: &str) {
+    let key: [u8; 16] = std::array::from_fn(|i| 0xc0 + i as u8);
+    let ccm = Ccm::new(AES128::new(key), tag_len, 2);
+    let packet: Vec<u8> = (0..total).collect();
+    let (aad, plaintext) = packet.split_at(header);
+
+    let sealed = ccm.seal(&hex(nonce), aad, plaintext);
+    assert_eq!(sealed, hex(expected_tail));
+    assert_eq!(ccm.open(&hex(nonce), aad, &sealed), Ok(plaintext.to_vec()));
+}
+
+#[test]
+fn rfc3610_packets_1_to_6() {
+    check_packet(8, "00000003020100a0a1a2a3a4a5", 31, 8,
+        "588c979a61c663d2f066d0c2c0f989806d5f6b61dac38417e8d12cfdf926e0");
+    check_packet(8, "00000004030201a0a1a2a3a4a5", 32, 8,
+        "72c91a36e135f8cf291ca894085c87e3cc15c439c9e43a3ba091d56e10400916");
+    check_packet(8, "00000005040302a0a1a2a3a4a5", 33, 8,
+        "51b1e5f44a197d1da46b0f8e2d282ae871e838bb64da8596574adaa76fbd9fb0c5");
+    check_packet(8, "00000006050403a0a1a2a3a4a5", 31, 12,
+        "a28c6865939a9a79faaa5c4c2a9d4a91cdac8c96c861b9c9e61ef1");
+    check_packet(8, "00000007060504a0a1a2a3a4a5", 32, 12,
+        "dcf1fb7b5d9e23fb9d4e131253658ad86ebdca3e51e83f077d9c2d93");
+    check_packet(8, "00000008070605a0a1a2a3a4a5", 33, 12,
+        "6fc1b011f006568b5171a42d953d469b2570a4bd87405a0443ac91cb94");
+}
+
+#[test]
+fn rfc3610_packets_7_to_12() {
+    check_packet(10, "00000009080706a0a1a2a3a4a5", 31, 8,
+        "0135d1b2c95f41d5d1d4fec185d166b8094e999dfed96c048c56602c97acbb7490");
+    check_packet(10, "0000000a090807a0a1a2a3a4a5", 32, 8,
+        "7b75399ac0831dd2f0bbd75879a2fd8f6cae6b6cd9b7db24c17b4433f434963f34b4");
+    check_packet(10, "0000000b0a0908a0a1a2a3a4a5", 33, 8,
+        "82531a60cc24945a4b8279181ab5c84df21ce7f9b73f42e197ea9c07e56b5eb17e5f4e");
+    check_packet(10, "0000000c0b0a09a0a1a2a3a4a5", 31, 12,
+        "07342594157785152b074098330abb141b947b566aa9406b4d999988dd");
+    check_packet(10, "0000000d0c0b0aa0a1a2a3a4a5", 32, 12,
+        "676bb20380b0e301e8ab79590a396da78b834934f53aa2e9107a8b6c022c");
+    check_packet(10, "0000000e0d0c0ba0a1a2a3a4a5", 33, 12,
+        "c0ffa0d6f05bdb67f24d43a4338d2aa4bed7b20e43cd1aa31662e7ad65d6db");
+}
+
+#[test]
+fn long_length_field_and_long_aad() {
+    // M = 16, L = 8 (7-byte nonce) with 70000 bytes of associated data, which needs the
+    // 0xfffe length encoding
+    let key: [u8; 16] = std::array::from_fn(|i| 0x40 + i as u8);
+    let ccm = Ccm::new(AES128::new(key), 16, 8);
+    assert_eq!(ccm.nonce_len(), 7);
+
+    let nonce = hex("10111213141516");
+    let aad: Vec<u8> = (0..70000).map(|i| (i % 251) as u8).collect();
+    let plaintext = b"Counter with CBC-MAC, L = 8";
+
+    let sealed = ccm.seal(&nonce, &aad, plaintext);
+    assert_eq!(sealed, hex("122c56169011c1190658f0ec307f9fb8b14a4cfd437f89c46c7939d62551bc614ad8b5c3485e9aa5d97f43"));
+    assert_eq!(ccm.open(&nonce, &aad, &sealed), Ok(plaintext.to_vec()));
+}
+
+#[test]
+fn short_tag_empty_message() {
+    // M = 4, L = 2 (13-byte nonce), nothing to encrypt or authenticate but the nonce
+    let key: [u8; 16] = std::array::from_fn(|i| 0x40 + i as u8);
+    let ccm = Ccm::new(AES128::new(key), 4, 2);
+    let nonce = hex("101112131415161718191a1b1c");
+
+    let sealed = ccm.seal(&nonce, b"", b"");
+    assert_eq!(sealed, hex("2a0b1a47"));
+    assert_eq!(ccm.open(&nonce, b"", &sealed), Ok(Vec::new()));
+}
+
+#[test]
+fn open_rejects_tampering() {
+    let ccm = Ccm::new(AES128::new([3u8; 16]), 8, 2);
+    let nonce = [5u8; 13];
+    let sealed = ccm.seal(&nonce, b"header", b"payload bytes");
+
+    for i in 0..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 0x01;
+        assert_eq!(ccm.open(&nonce, b"header", &tampered), Err(AuthError), "flip at byte {}", i);
+    }
+    assert_eq!(ccm.open(&nonce, b"headex", &sealed), Err(AuthError));
+    assert_eq!(ccm.open(&nonce, b"header", &sealed[..4]), Err(AuthError));
+}
+
+#[test]
+fn open_rejects_a_nonce_of_the_wrong_length() {
+    let ccm = Ccm::new(AES128::new([3u8; 16]), 8, 2);
+    let sealed = ccm.seal(&[5u8; 13], b"header", b"payload bytes");
+    assert_eq!(ccm.open(&[5u8; 12], b"header", &sealed), Err(AuthError));
+    assert_eq!(ccm.open(&[5u8; 14], b"header", &sealed), Err(AuthError));
+}
+
+#[test]
+#[should_panic(expected = "too long")]
+fn seal_rejects_message_longer_than_length_field() {
+    let ccm = Ccm::new(AES128::new([0u8; 16]), 8, 2);
+    ccm.seal(&[0u8; 13], b"", &vec![0u8; 1 << 16]);
+}
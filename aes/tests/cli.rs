@@ -0,0 +1,143 @@
+//! Test: the command-line tool. Fixed-IV outputs were checked against `openssl enc`; exit
+//! codes are checked by running the built binary.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use aes::aes_for_key;
+use aes::cli::{run, CliError};
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f";
+const IV: &str = "0f0e0d0c0b0a09080706050403020100";
+const MESSAGE: &[u8] = b"hello world, 27 bytes long!";
+
+fn aes(args: &str, input: &[u8]) -> Result<Vec<u8>, CliError> {
+    let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+    let mut output = Vec::new();
+    run(&args, &aes_for_key, &mut &input[..], &mut output)?;
+    Ok(output)
+}
+
+// Runs the binary and returns its exit status and stdout
+fn aes_binary(args: &[&str], input: &[u8]) -> (i32, Vec<u8>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_aes"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // a usage error can exit before reading stdin, which is not a failure here
+    let _ = child.stdin.take().unwrap().write_all(input);
+    let output = child.wait_with_output().unwrap();
+    (output.status.code().unwrap(), output.stdout)
+}
+
+#[test]
+fn fixed_iv_output_matches_openssl() {
+    let cases = [
+        (format!("encrypt -m cbc -k {} --iv {} --out hex", KEY, IV),
+            "129aa1fca56557fcab6b19d51805c7efa10363f793432012c410845793f66e1d\n"),
+        (format!("encrypt -m ctr -k {} --iv {} --out hex", KEY, IV),
+            "48cc95fedb6c2c87767398f04c9cae4a25dfd0c0067e0c2b00d5b0\n"),
+        (format!("encrypt -m ecb -k {} --out base64", KEY),
+            "Ws4Nw1009daYGyxZXmi2Vq0UgwcKchceHOcD5ZSQ+S0=\n"),
+    ];
+    for (args, expected) in cases {
+        assert_eq!(String::from_utf8(aes(&args, MESSAGE).unwrap()).unwrap(), expected, "{}", args);
+    }
+}
+
+#[test]
+fn every_mode_round_trips_with_a_generated_iv() {
+    for mode in ["ecb", "cbc", "ctr", "gcm"] {
+        for key in [KEY, &"ab".repeat(24), &"cd".repeat(32)] {
+            let sealed = aes(&format!("encrypt -m {} -k {} --out base64", mode, key), MESSAGE).unwrap();
+            let opened = aes(&format!("decrypt -m {} -k {} --in base64", mode, key), &sealed).unwrap();
+            assert_eq!(opened, MESSAGE, "{} with a {}-byte key", mode, key.len() / 2);
+        }
+    }
+
+    // the IV is random and prepended, so two encryptions differ
+    let args = format!("encrypt -k {}", KEY);
+    let (first, second) = (aes(&args, MESSAGE).unwrap(), aes(&args, MESSAGE).unwrap());
+    assert_eq!(first.len(), 16 + 32);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn gcm_checks_the_tag_and_associated_data() {
+    let sealed = aes(&format!("encrypt -m gcm -k {} --aad 0102", KEY), MESSAGE).unwrap();
+    assert_eq!(sealed.len(), 12 + MESSAGE.len() + 16);
+    assert_eq!(aes(&format!("decrypt -m gcm -k {} --aad 0102", KEY), &sealed).unwrap(), MESSAGE);
+    assert!(matches!(aes(&format!("decrypt -m gcm -k {} --aad 0103", KEY), &sealed), Err(CliError::Input(_))));
+
+    let mut tampered = sealed.clone();
+    tampered[20] ^= 1;
+    assert!(matches!(aes(&format!("decrypt -m gcm -k {} --aad 0102", KEY), &tampered), Err(CliError::Input(_))));
+}
+
+#[test]
+fn no_padding_requires_whole_blocks() {
+    let args = format!("encrypt -m cbc -k {} --iv {} --no-padding", KEY, IV);
+    assert_eq!(aes(&args, &[0u8; 32]).unwrap().len(), 32);
+    assert!(matches!(aes(&args, MESSAGE), Err(CliError::Input(_))));
+}
+
+#[test]
+fn key_file_takes_hex_or_raw_bytes() {
+    let dir = std::env::temp_dir();
+    let hex_file = dir.join(format!("aes-cli-test-{}.hex", std::process::id()));
+    let raw_file = dir.join(format!("aes-cli-test-{}.bin", std::process::id()));
+    std::fs::write(&hex_file, format!("{}\n", KEY)).unwrap();
+    std::fs::write(&raw_file, (0u8..16).collect::<Vec<_>>()).unwrap();
+
+    let expected = aes(&format!("encrypt -m ecb -k {}", KEY), MESSAGE).unwrap();
+    for file in [&hex_file, &raw_file] {
+        let args = format!("encrypt -m ecb --key-file {}", file.display());
+        assert_eq!(aes(&args, MESSAGE).unwrap(), expected);
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[test]
+fn kattis_mode_is_raw_ecb() {
+    let mut input: Vec<u8> = (0u8..16).collect();
+    input.extend_from_slice(b"0123456789abcdef0123");
+    let expected = aes::aes_128_ecb_encrypt(b"0123456789abcdef", &(0u8..16).collect::<Vec<_>>().try_into().unwrap());
+
+    // the trailing partial block is dropped, and no arguments means Kattis mode
+    assert_eq!(aes("--kattis", &input).unwrap(), expected);
+    assert_eq!(aes("", &input).unwrap(), expected);
+    assert_eq!(aes_binary(&[], &input), (0, expected));
+
+    // input that ends inside the key is rejected rather than an I/O error
+    let err = aes("--kattis 256", &input[..20]).unwrap_err();
+    assert_eq!((err.exit_code(), err.to_string()), (1, "input ends before the 256-bit key".to_string()));
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(aes_binary(&["encrypt", "-k", KEY], MESSAGE).0, 0);
+    // usage errors
+    assert_eq!(aes_binary(&["frobnicate"], MESSAGE).0, 2);
+    assert_eq!(aes_binary(&["encrypt"], MESSAGE).0, 2);
+    assert_eq!(aes_binary(&["encrypt", "-k", "0011"], MESSAGE).0, 2);
+    assert_eq!(aes_binary(&["encrypt", "-k", KEY, "-m", "xts"], MESSAGE).0, 2);
+    assert_eq!(aes_binary(&["encrypt", "-k", KEY, "--iv", "00"], MESSAGE).0, 2);
+    assert_eq!(aes_binary(&["--kattis", "512"], MESSAGE).0, 2);
+    // rejected input
+    assert_eq!(aes_binary(&["decrypt", "-k", KEY, "--in", "hex"], b"zz").0, 1);
+    assert_eq!(aes_binary(&["decrypt", "-k", KEY], &[0u8; 20]).0, 1);
+    assert_eq!(aes_binary(&["decrypt", "-m", "gcm", "-k", KEY], &[0u8; 40]).0, 1);
+    assert_eq!(aes_binary(&[], &[0u8; 10]).0, 1);
+}
+
+#[test]
+fn help_is_shown_wherever_the_flag_is() {
+    for args in [&["--help"][..], &["-h"], &["encrypt", "--help"], &["decrypt", "-k", KEY, "-h"], &["--kattis", "--help"]] {
+        let (status, stdout) = aes_binary(args, b"");
+        assert_eq!(status, 0, "{:?}", args);
+        assert!(stdout.starts_with(b"usage: aes (encrypt | decrypt)"), "{:?}", args);
+    }
+}
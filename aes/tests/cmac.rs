@@ -0,0 +1,96 @@
+//! Test: AES-CMAC against the RFC 4493 §4 examples and SP 800-38B Appendix D.
+
+use aes::{cbc_mac, cmac, AuthError, Cmac, AES128, AES192, AES256};
+
+const MESSAGE: &str = "6bc1bee22e409f96e93d7e117393172a ae2d8a571e03ac9c9eb76fac45af8e51
+                       30c81c46a35ce411e5fbc1191a0a52ef f69f2445df4f9b17ad2b417be66c3710";
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+fn aes128() -> AES128 {
+    AES128::new(hex("2b7e151628aed2a6abf7158809cf4f3c").try_into().unwrap())
+}
+
+#[test]
+fn rfc4493_examples() {
+    let message = hex(MESSAGE);
+    for (len, tag) in [
+        (0, "bb1d6929e95937287fa37d129b756746"),
+        (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+        (40, "dfa66747de9ae63030ca32611497c827"),
+        (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+    ] {
+        assert_eq!(cmac(&aes128(), &message[..len]).to_vec(), hex(tag), "Mlen = {}", len);
+    }
+}
+
+#[test]
+fn sp800_38b_aes192_and_aes256() {
+    let message = hex(MESSAGE);
+    let aes192 = AES192::new(hex("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b").try_into().unwrap());
+    let aes256 = AES256::new(hex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4").try_into().unwrap());
+
+    assert_eq!(cmac(&aes192, &message).to_vec(), hex("a1d5df0eed790f794d77589659f39a11"));
+    assert_eq!(cmac(&aes256, &message).to_vec(), hex("e1992190549f6ed5696a2c056c315410"));
+}
+
+#[test]
+fn incremental_updates_match_one_shot() {
+    let message = hex(MESSAGE);
+    for len in [0, 15, 16, 17, 40, 64] {
+        let expected = cmac(&aes128(), &message[..len]);
+        for chunk in [1, 7, 16, 33] {
+            let mut mac = Cmac::new(aes128());
+            for piece in message[..len].chunks(chunk) {
+                mac.update(piece);
+            }
+            assert_eq!(mac.finalize(), expected, "Mlen = {}, chunks of {}", len, chunk);
+        }
+    }
+}
+
+#[test]
+fn verify_accepts_full_and_truncated_tags_only() {
+    let message = hex(MESSAGE);
+    let tag = hex("51f0bebf7e3b9d92fc49741779363cfe");
+    let verify = |tag: &[u8]| {
+        let mut mac = Cmac::new(aes128());
+        mac.update(&message);
+        mac.verify(tag)
+    };
+
+    assert_eq!(verify(&tag), Ok(()));
+    assert_eq!(verify(&tag[..8]), Ok(()));
+    assert_eq!(verify(&tag[..4]), Err(AuthError), "tags below 64 bits are refused");
+
+    let mut wrong = tag.clone();
+    wrong[15] ^= 1;
+    assert_eq!(verify(&wrong), Err(AuthError));
+}
+
+#[test]
+fn cbc_mac_length_extension_does_not_carry_over_to_cmac() {
+    // The textbook forgery: knowing t = MAC(m) for a one-block m, the two-block message
+    // m || (m xor t) has the same CBC-MAC, because the second block encrypts m again
+    let aes = aes128();
+    let m = b"pay alice 100 kr".to_vec();
+    let t = cbc_mac(&aes, &[0u8; 16], &m);
+
+    let mut forged = m.clone();
+    forged.extend(m.iter().zip(&t).map(|(a, b)| a ^ b));
+    assert_eq!(cbc_mac(&aes, &[0u8; 16], &forged), t);
+
+    // the same trick with a CMAC tag fails: the subkey enters the last block only
+    let t = cmac(&aes, &m);
+    let mut forged = m.clone();
+    forged.extend(m.iter().zip(&t).map(|(a, b)| a ^ b));
+    let mut mac = Cmac::new(&aes);
+    mac.update(&forged);
+    assert_eq!(mac.verify(&t), Err(AuthError));
+}
@@ -0,0 +1,68 @@
+//! Test: a single-byte fault spreads through the last rounds the way differential fault
+//! analysis expects, on every key size.
+
+use aes::block_cipher::with_state;
+use aes::{BlockCipher, Fault, State, AES128, AES192, AES256};
+
+const PLAINTEXT: [u8; 16] = [0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07, 0x34];
+
+fn differing_bytes(a: &[u8; 16], b: &[u8; 16]) -> Vec<usize> {
+    (0..16).filter(|&i| a[i] != b[i]).collect()
+}
+
+#[test]
+fn last_round_fault_changes_one_shifted_byte() {
+    let aes = AES128::new([0x2b; 16]);
+    let mut correct = PLAINTEXT;
+    aes.encrypt_block(&mut correct);
+    for position in 0..16 {
+        let mut faulty = PLAINTEXT;
+        with_state(&mut faulty, |state| aes.cipher_with_fault(state, Fault::new(10, position, 0x01)));
+        // ShiftRows moves row r left by r columns
+        let (row, col) = (position % 4, position / 4);
+        assert_eq!(differing_bytes(&correct, &faulty), [4 * ((col + 4 - row) % 4) + row]);
+    }
+}
+
+#[test]
+fn earlier_faults_spread_to_a_column_then_the_whole_block() {
+    let aes = AES128::new_portable([0x7e; 16]);
+    let mut correct = PLAINTEXT;
+    aes.encrypt_block(&mut correct);
+
+    let mut faulty = PLAINTEXT;
+    with_state(&mut faulty, |state| aes.cipher_with_fault(state, Fault::new(9, 0, 0x80)));
+    // byte 0 stays in column 0 through round 9, whose bytes land on 0, 13, 10 and 7
+    assert_eq!(differing_bytes(&correct, &faulty), [0, 7, 10, 13]);
+
+    let mut faulty = PLAINTEXT;
+    with_state(&mut faulty, |state| aes.cipher_with_fault(state, Fault::new(8, 6, 0x55)));
+    assert_eq!(differing_bytes(&correct, &faulty).len(), 16);
+}
+
+fn assert_every_round_is_faulted(cipher: &dyn BlockCipher, rounds: usize, with_fault: impl Fn(&mut State, Fault)) {
+    let mut correct = PLAINTEXT;
+    cipher.encrypt_block(&mut correct);
+    for round in 1..=rounds {
+        let mut faulty = PLAINTEXT;
+        with_state(&mut faulty, |state| with_fault(state, Fault::new(round, 3, 0x10)));
+        assert_ne!(faulty, correct, "round {} of {}", round, rounds);
+    }
+}
+
+#[test]
+fn every_key_size_takes_faults_in_every_round() {
+    let aes128 = AES128::new([1; 16]);
+    assert_every_round_is_faulted(&aes128, 10, |state, fault| aes128.cipher_with_fault(state, fault));
+    let aes192 = AES192::new([2; 24]);
+    assert_every_round_is_faulted(&aes192, 12, |state, fault| aes192.cipher_with_fault(state, fault));
+    let aes256 = AES256::new([3; 32]);
+    assert_every_round_is_faulted(&aes256, 14, |state, fault| aes256.cipher_with_fault(state, fault));
+}
+
+#[test]
+#[should_panic(expected = "no round 11")]
+fn rejects_a_round_past_the_end() {
+    let mut block = PLAINTEXT;
+    with_state(&mut block, |state| AES128::new([0; 16]).cipher_with_fault(state, Fault::new(11, 0, 1)));
+}
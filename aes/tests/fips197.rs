@@ -0,0 +1,91 @@
+//! Test: FIPS-197 Appendix C example vectors for AES-128, AES-192 and AES-256.
+
+use aes::{
+    aes_128_ecb_decrypt, aes_128_ecb_encrypt, aes_192_ecb_decrypt, aes_192_ecb_encrypt,
+    aes_256_ecb_decrypt, aes_256_ecb_encrypt, State, AES128, AES192, AES256,
+};
+
+const PLAINTEXT: [u8; 16] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+
+// Key bytes 00 01 02 ... used by every Appendix C example
+fn counting_key<const N: usize>() -> [u8; N] {
+    let mut key = [0u8; N];
+    for (i, b) in key.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    key
+}
+
+#[test]
+fn appendix_c1_aes128() {
+    let expected: [u8; 16] = [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a];
+
+    let aes = AES128::new(counting_key());
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), expected, "AES-128 ciphertext must match FIPS-197 C.1");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "AES-128 inverse cipher must match FIPS-197 C.1");
+}
+
+#[test]
+fn appendix_c2_aes192() {
+    let expected: [u8; 16] = [0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91];
+
+    let aes = AES192::new(counting_key());
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), expected, "AES-192 ciphertext must match FIPS-197 C.2");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "AES-192 inverse cipher must match FIPS-197 C.2");
+}
+
+#[test]
+fn appendix_c3_aes256() {
+    let expected: [u8; 16] = [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89];
+
+    let aes = AES256::new(counting_key());
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), expected, "AES-256 ciphertext must match FIPS-197 C.3");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "AES-256 inverse cipher must match FIPS-197 C.3");
+}
+
+#[test]
+fn ecb_helpers_match_single_block_cipher() {
+    let plaintext = [PLAINTEXT, PLAINTEXT].concat();
+
+    let ct128 = aes_128_ecb_encrypt(&plaintext, &counting_key());
+    assert_eq!(ct128[..16], ct128[16..], "ECB encrypts equal blocks to equal blocks");
+    assert_eq!(aes_128_ecb_decrypt(&ct128, &counting_key()), plaintext);
+
+    let ct192 = aes_192_ecb_encrypt(&plaintext, &counting_key());
+    assert_eq!(ct192[..16], [0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91]);
+    assert_eq!(aes_192_ecb_decrypt(&ct192, &counting_key()), plaintext);
+
+    let ct256 = aes_256_ecb_encrypt(&plaintext, &counting_key());
+    assert_eq!(ct256[..16], [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89]);
+    assert_eq!(aes_256_ecb_decrypt(&ct256, &counting_key()), plaintext);
+}
+
+#[cfg(feature = "ttable")]
+#[test]
+fn appendix_c1_ttable_aes128() {
+    let expected: [u8; 16] = [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a];
+
+    let aes = aes::TTableAES128::new(counting_key());
+    let mut state = State::new(PLAINTEXT);
+
+    aes.cipher(&mut state);
+    assert_eq!(*state.as_bytes(), expected, "T-table ciphertext must match FIPS-197 C.1");
+
+    aes.inv_cipher(&mut state);
+    assert_eq!(*state.as_bytes(), PLAINTEXT, "T-table inverse cipher must match FIPS-197 C.1");
+}
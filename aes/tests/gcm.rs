@@ -0,0 +1,193 @@
+//! Test: AES-GCM against the test cases of the GCM specification (McGrew & Viega), which
+//! NIST reuses in its GCM validation material.
+
+use aes::{AuthError, BlockCipher, Gcm, AES128, AES192, AES256};
+
+const K: &str = "feffe9928665731c6d6a8f9467308308";
+const P: &str = "d9313225f88406e5a55909c5aff5269a 86a7a9531534f7da2e4c303d8a318a72
+                 1c3c0c95956809532fcf0e2449a6b525 b16aedf5aa0de657ba637b39";
+const A: &str = "feedfacedeadbeeffeedfacedeadbeef abaddad2";
+const IV: &str = "cafebabefacedbaddecaf888";
+const IV_64: &str = "cafebabefacedbad";
+const IV_480: &str = "9313225df88406e555909c5aff5269aa 6a7a9538534f7da1e4c303d2a318a728
+                      c3c0c95156809539fcf0e2429a6b5254 16aedbf5a0de6a57a637b39b";
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+fn cipher_for(key: &[u8]) -> Box<dyn BlockCipher> {
+    match key.len() {
+        16 => Box::new(AES128::new(key.try_into().unwrap())),
+        24 => Box::new(AES192::new(key.try_into().unwrap())),
+        32 => Box::new(AES256::new(key.try_into().unwrap())),
+        n => panic!("no AES variant with a {}-byte key", n),
+    }
+}
+
+fn check(key: &str, iv: &str, plaintext: &str, aad: &str, ciphertext: &str, tag: &str) {
+    let gcm = Gcm::new(cipher_for(&hex(key)));
+    let (iv, plaintext, aad) = (hex(iv), hex(plaintext), hex(aad));
+    let expected = [hex(ciphertext), hex(tag)].concat();
+
+    let sealed = gcm.seal(&iv, &aad, &plaintext);
+    assert_eq!(sealed, expected);
+    assert_eq!(gcm.open(&iv, &aad, &sealed), Ok(plaintext));
+}
+
+#[test]
+fn test_case_1_empty() {
+    check(&"00".repeat(16), &"00".repeat(12), "", "", "", "58e2fccefa7e3061367f1d57a4e7455a");
+}
+
+#[test]
+fn test_case_2_one_block() {
+    check(
+        &"00".repeat(16),
+        &"00".repeat(12),
+        &"00".repeat(16),
+        "",
+        "0388dace60b6a392f328c2b971b2fe78",
+        "ab6e47d42cec13bdf53a67b21257bddf",
+    );
+}
+
+#[test]
+fn test_case_3_four_blocks() {
+    check(
+        K,
+        IV,
+        &format!("{} 1aafd255", P),
+        "",
+        "42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+         21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091473f5985",
+        "4d5c2af327cd64a62cf35abd2ba6fab4",
+    );
+}
+
+#[test]
+fn test_case_4_aad_and_partial_block() {
+    check(
+        K,
+        IV,
+        P,
+        A,
+        "42831ec2217774244b7221b784d0d49c e3aa212f2c02a4e035c17e2329aca12e
+         21d514b25466931c7d8f6a5aac84aa05 1ba30b396a0aac973d58e091",
+        "5bc94fbc3221a5db94fae95ae7121a47",
+    );
+}
+
+#[test]
+fn test_case_5_short_iv() {
+    check(
+        K,
+        IV_64,
+        P,
+        A,
+        "61353b4c2806934a777ff51fa22a4755 699b2a714fcdc6f83766e5f97b6c7423
+         73806900e49f24b22b097544d4896b42 4989b5e1ebac0f07c23f4598",
+        "3612d2e79e3b0785561be14aaca2fccb",
+    );
+}
+
+#[test]
+fn test_case_6_long_iv() {
+    check(
+        K,
+        IV_480,
+        P,
+        A,
+        "8ce24998625615b603a033aca13fb894 be9112a5c3a211a8ba262a3cca7e2ca7
+         01e4a9a4fba43c90ccdcb281d48c7c6f d62875d2aca417034c34aee5",
+        "619cc5aefffe0bfa462af43c1699d050",
+    );
+}
+
+#[test]
+fn test_case_10_aes192() {
+    check(
+        &format!("{} feffe9928665731c", K),
+        IV,
+        P,
+        A,
+        "3980ca0b3c00e841eb06fac4872a2757 859e1ceaa6efd984628593b40ca1e19c
+         7d773d00c144c525ac619d18c84a3f47 18e2448b2fe324d9ccda2710",
+        "2519498e80f1478f37ba55bd6d27618c",
+    );
+}
+
+#[test]
+fn test_case_16_aes256() {
+    check(
+        &format!("{} {}", K, K),
+        IV,
+        P,
+        A,
+        "522dc1f099567d07f47f37a32a84427d 643a8cdcbfe5c0c97598a2bd2555d1aa
+         8cb08e48590dbb3da7b08b1056828838 c5f61e6393ba7a0abcc9f662",
+        "76fc6ece0f4e1768cddf8853bb2d551b",
+    );
+}
+
+#[test]
+fn test_case_18_aes256_long_iv() {
+    check(
+        &format!("{} {}", K, K),
+        IV_480,
+        P,
+        A,
+        "5a8def2f0c9e53f1f75d7853659e2a20 eeb2b22aafde6419a058ab4f6f746bf4
+         0fc0c3b780f244452da3ebf1c5d82cde a2418997200ef82e44ae7e3f",
+        "a44a8266ee1c8eb0c8b5d4cf5ae9f19a",
+    );
+}
+
+#[test]
+fn truncated_tag_is_a_prefix_of_the_full_tag() {
+    let key = hex(K);
+    let full = Gcm::new(cipher_for(&key)).seal(&hex(IV), &hex(A), &hex(P));
+    let gcm = Gcm::with_tag_len(cipher_for(&key), 8);
+    let sealed = gcm.seal(&hex(IV), &hex(A), &hex(P));
+
+    assert_eq!(sealed.len(), hex(P).len() + 8);
+    assert_eq!(sealed, full[..sealed.len()]);
+    assert_eq!(gcm.open(&hex(IV), &hex(A), &sealed), Ok(hex(P)));
+}
+
+#[test]
+fn open_rejects_any_modification() {
+    let gcm = Gcm::new(cipher_for(&hex(K)));
+    let (iv, aad) = (hex(IV), hex(A));
+    let sealed = gcm.seal(&iv, &aad, &hex(P));
+
+    // every single-bit flip, in ciphertext or tag, must fail
+    for i in 0..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 0x80;
+        assert_eq!(gcm.open(&iv, &aad, &tampered), Err(AuthError), "flip at byte {}", i);
+    }
+
+    let mut other_aad = aad.clone();
+    other_aad[0] ^= 1;
+    assert_eq!(gcm.open(&iv, &other_aad, &sealed), Err(AuthError));
+    assert_eq!(gcm.open(&hex(IV_64), &aad, &sealed), Err(AuthError));
+    assert_eq!(gcm.open(&iv, &aad, &sealed[..10]), Err(AuthError));
+}
+
+#[test]
+fn open_rejects_an_empty_iv() {
+    let gcm = Gcm::new(cipher_for(&hex(K)));
+    let sealed = gcm.seal(&hex(IV), &hex(A), &hex(P));
+    assert_eq!(gcm.open(&[], &hex(A), &sealed), Err(AuthError));
+}
+
+#[test]
+#[should_panic(expected = "GCM tags")]
+fn rejects_unsupported_tag_length() {
+    Gcm::with_tag_len(AES128::new([0u8; 16]), 10);
+}
@@ -0,0 +1,94 @@
+//! Test: the round keys match the FIPS-197 Appendix A expansion examples, and the schedule
+//! inverts back to the cipher key from every round index.
+
+use aes::encoding::from_hex;
+use aes::key_schedule::{aes128_key_from_round_key, expand_key, invert_key_schedule};
+use aes::{AES128, AES192, AES256};
+
+// FIPS-197 Appendix A.1, w[0..43] four words per line
+const A1_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const A1_ROUND_KEYS: [&str; 11] = [
+    "2b7e151628aed2a6abf7158809cf4f3c",
+    "a0fafe1788542cb123a339392a6c7605",
+    "f2c295f27a96b9435935807a7359f67f",
+    "3d80477d4716fe3e1e237e446d7a883b",
+    "ef44a541a8525b7fb671253bdb0bad00",
+    "d4d1c6f87c839d87caf2b8bc11f915bc",
+    "6d88a37a110b3efddbf98641ca0093fd",
+    "4e54f70e5f5fc9f384a64fb24ea6dc4f",
+    "ead27321b58dbad2312bf5607f8d292f",
+    "ac7766f319fadc2128d12941575c006e",
+    "d014f9a8c9ee2589e13f0cc8b6630ca6",
+];
+
+// Appendix A.2 and A.3 keys with their last round keys, ending in w[51] and w[59]
+const A2_KEY: &str = "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b";
+const A2_LAST: &str = "e98ba06f448c773c8ecc720401002202";
+const A3_KEY: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+const A3_LAST: &str = "fe4890d1e6188d0b046df344706c631e";
+
+fn flatten(round_keys: &[aes::State]) -> Vec<u8> {
+    round_keys.iter().flat_map(|k| *k.as_bytes()).collect()
+}
+
+#[test]
+fn appendix_a1_expansion() {
+    let round_keys = expand_key(&from_hex(A1_KEY).unwrap());
+    assert_eq!(round_keys.len(), 11);
+    for (round, expected) in A1_ROUND_KEYS.iter().enumerate() {
+        assert_eq!(round_keys[round].as_bytes().to_vec(), from_hex(expected).unwrap(), "round key {}", round);
+    }
+}
+
+#[test]
+fn appendix_a2_a3_last_round_keys() {
+    let round_keys = expand_key(&from_hex(A2_KEY).unwrap());
+    assert_eq!(round_keys.len(), 13);
+    assert_eq!(round_keys[12].as_bytes().to_vec(), from_hex(A2_LAST).unwrap());
+
+    let round_keys = expand_key(&from_hex(A3_KEY).unwrap());
+    assert_eq!(round_keys.len(), 15);
+    assert_eq!(round_keys[14].as_bytes().to_vec(), from_hex(A3_LAST).unwrap());
+}
+
+#[test]
+fn ciphers_expose_the_same_round_keys() {
+    let key: [u8; 16] = from_hex(A1_KEY).unwrap().try_into().unwrap();
+    assert_eq!(AES128::new(key).round_keys(), expand_key(&key));
+    assert_eq!(AES128::new_portable(key).round_keys(), expand_key(&key));
+
+    let key: [u8; 24] = from_hex(A2_KEY).unwrap().try_into().unwrap();
+    assert_eq!(AES192::new(key).round_keys(), expand_key(&key));
+    let key: [u8; 32] = from_hex(A3_KEY).unwrap().try_into().unwrap();
+    assert_eq!(AES256::new(key).round_keys(), expand_key(&key));
+}
+
+#[test]
+fn aes128_inverts_from_every_round_key() {
+    let key: [u8; 16] = from_hex(A1_KEY).unwrap().try_into().unwrap();
+    for (round, round_key) in A1_ROUND_KEYS.iter().enumerate() {
+        let round_key: [u8; 16] = from_hex(round_key).unwrap().try_into().unwrap();
+        assert_eq!(aes128_key_from_round_key(&round_key, round), key, "round key {}", round);
+    }
+}
+
+#[test]
+fn aes192_and_aes256_invert_from_every_window() {
+    for key in [from_hex(A2_KEY).unwrap(), from_hex(A3_KEY).unwrap()] {
+        let schedule = flatten(&expand_key(&key));
+        // every window of key-length bytes that starts on a round key and fits in the schedule
+        let mut round = 0;
+        while 16 * round + key.len() <= schedule.len() {
+            let window = &schedule[16 * round..16 * round + key.len()];
+            assert_eq!(invert_key_schedule(window, round), key, "AES-{} round key {}", key.len() * 8, round);
+            round += 1;
+        }
+        assert_eq!(round, key.len() / 4 + 6);
+    }
+}
+
+#[test]
+#[should_panic(expected = "AES-256 has no 8 words from round key 14")]
+fn aes256_needs_two_round_keys() {
+    invert_key_schedule(&[0u8; 32], 14);
+}
@@ -0,0 +1,88 @@
+//! Test: AES key wrap against the RFC 3394 §4 and RFC 5649 §6 vectors.
+
+use aes::{kw_unwrap, kw_wrap, kwp_unwrap, kwp_wrap, BlockCipher, IntegrityError, AES128, AES192, AES256};
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+fn cipher_for(key: &[u8]) -> Box<dyn BlockCipher> {
+    match key.len() {
+        16 => Box::new(AES128::new(key.try_into().unwrap())),
+        24 => Box::new(AES192::new(key.try_into().unwrap())),
+        32 => Box::new(AES256::new(key.try_into().unwrap())),
+        n => panic!("no AES variant with a {}-byte key", n),
+    }
+}
+
+// RFC 3394 uses KEK 00 01 02 ... and key data 00 11 22 ... ff 00 01 02 ... truncated to size
+fn check_kw(kek_len: usize, data_len: usize, expected: &str) {
+    let kek: Vec<u8> = (0..kek_len as u8).collect();
+    let key_data = &hex("00112233445566778899aabbccddeeff 000102030405060708090a0b0c0d0e0f")[..data_len];
+    let kek = cipher_for(&kek);
+
+    let wrapped = kw_wrap(&kek, key_data);
+    assert_eq!(wrapped, hex(expected));
+    assert_eq!(kw_unwrap(&kek, &wrapped), Ok(key_data.to_vec()));
+}
+
+#[test]
+fn rfc3394_4_1_to_4_3_128_bit_key_data() {
+    check_kw(16, 16, "1fa68b0a8112b447 aef34bd8fb5a7b82 9d3e862371d2cfe5");
+    check_kw(24, 16, "96778b25ae6ca435 f92b5b97c050aed2 468ab8a17ad84e5d");
+    check_kw(32, 16, "64e8c3f9ce0f5ba2 63e9777905818a2a 93c8191e7d6e8ae7");
+}
+
+#[test]
+fn rfc3394_4_4_to_4_6_wider_key_data() {
+    check_kw(24, 24, "031d33264e15d332 68f24ec260743edc e1c6c7ddee725a93 6ba814915c6762d2");
+    check_kw(32, 24, "a8f9bc1612c68b3f f6e6f4fbe30e71e4 769c8b80a32cb895 8cd5d17d6b254da1");
+    check_kw(32, 32, "28c9f404c4b810f4 cbccb35cfb87f826 3f5786e2d80ed326 cbc7f0e71a99f43b fb988b9b7a02dd21");
+}
+
+#[test]
+fn rfc5649_6_vectors() {
+    let kek = cipher_for(&hex("5840df6e29b02af1 ab493b705bf16ea1 ae8338f4dcc176a8"));
+
+    let key_20 = hex("c37b7e6492584340 bed1220780894115 5068f738");
+    let wrapped = kwp_wrap(&kek, &key_20);
+    assert_eq!(wrapped, hex("138bdeaa9b8fa7fc 61f97742e72248ee 5ae6ae5360d1ae6a 5f54f373fa543b6a"));
+    assert_eq!(kwp_unwrap(&kek, &wrapped), Ok(key_20));
+
+    // 7 bytes fit into one semiblock, so this is a single block encryption
+    let key_7 = hex("466f7250617369");
+    let wrapped = kwp_wrap(&kek, &key_7);
+    assert_eq!(wrapped, hex("afbeb0f07dfbf541 9200f2ccb50bb24f"));
+    assert_eq!(kwp_unwrap(&kek, &wrapped), Ok(key_7));
+}
+
+#[test]
+fn unwrap_detects_tampering_and_wrong_kek() {
+    let kek = AES128::new([0x11; 16]);
+    let other = AES128::new([0x22; 16]);
+    let key_data = [0x5a; 32];
+
+    let wrapped = kw_wrap(&kek, &key_data);
+    for i in 0..wrapped.len() {
+        let mut tampered = wrapped.clone();
+        tampered[i] ^= 0x01;
+        assert_eq!(kw_unwrap(&kek, &tampered), Err(IntegrityError), "flip at byte {}", i);
+    }
+    assert_eq!(kw_unwrap(&other, &wrapped), Err(IntegrityError));
+    assert_eq!(kw_unwrap(&kek, &wrapped[..16]), Err(IntegrityError));
+    assert_eq!(kw_unwrap(&kek, &wrapped[..30]), Err(IntegrityError));
+
+    for len in [1, 8, 9, 31] {
+        let wrapped = kwp_wrap(&kek, &key_data[..len]);
+        assert_eq!(wrapped.len(), len.next_multiple_of(8) + 8);
+        assert_eq!(kwp_unwrap(&kek, &wrapped), Ok(key_data[..len].to_vec()));
+        assert_eq!(kwp_unwrap(&other, &wrapped), Err(IntegrityError));
+    }
+
+    // a KW output is not a valid KWP output: the initial values differ
+    assert_eq!(kwp_unwrap(&kek, &wrapped), Err(IntegrityError));
+}
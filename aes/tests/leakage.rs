@@ -0,0 +1,50 @@
+//! Test: the leakage hook reports every step of the cipher in order without changing its
+//! output.
+
+use aes::leakage::{Operation, Step};
+use aes::{State, AES128, AES256};
+
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+const PLAINTEXT: [u8; 16] = [0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37, 0x07, 0x34];
+
+fn steps(cipher: impl Fn(&mut State, &mut dyn FnMut(Step))) -> (State, Vec<(usize, Operation, State, State)>) {
+    let mut state = State::new(PLAINTEXT);
+    let mut steps = Vec::new();
+    cipher(&mut state, &mut |step| steps.push((step.round, step.operation, *step.before, *step.after)));
+    (state, steps)
+}
+
+#[test]
+fn reports_every_step_in_order() {
+    let aes = AES128::new(KEY);
+    let (output, steps) = steps(|state, leak| aes.cipher_with_leakage(state, leak));
+
+    let mut expected = State::new(PLAINTEXT);
+    aes.cipher(&mut expected);
+    assert_eq!(output, expected);
+
+    // initial AddRoundKey, 9 full rounds and a last round without MixColumns
+    assert_eq!(steps.len(), 1 + 9 * 4 + 3);
+    assert_eq!(steps[0].2, State::new(PLAINTEXT));
+    assert_eq!(steps.last().unwrap().3, expected);
+    for pair in steps.windows(2) {
+        assert_eq!(pair[0].3, pair[1].2, "each step starts where the last one ended");
+    }
+    assert!(steps.iter().all(|s| s.1 != Operation::MixColumns || s.0 < 10));
+
+    // FIPS-197 Appendix B: the first SubBytes reads plaintext ^ key and gives d4 27 11 ae ...
+    let (round, operation, before, after) = steps[1];
+    assert_eq!((round, operation), (1, Operation::SubBytes));
+    assert_eq!(*before.as_bytes(), std::array::from_fn(|i| PLAINTEXT[i] ^ KEY[i]));
+    assert_eq!(after.as_bytes()[..4], [0xd4, 0x27, 0x11, 0xae]);
+}
+
+#[test]
+fn aes256_has_fourteen_rounds_of_steps() {
+    let aes = AES256::new([7; 32]);
+    let (output, steps) = steps(|state, leak| aes.cipher_with_leakage(state, leak));
+    let mut expected = State::new(PLAINTEXT);
+    aes.cipher(&mut expected);
+    assert_eq!(output, expected);
+    assert_eq!(steps.len(), 1 + 13 * 4 + 3);
+}
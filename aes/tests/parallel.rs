@@ -0,0 +1,100 @@
+//! Test: the multi-threaded ECB, CTR and CBC-decrypt functions give the same output as the
+//! serial modes for every thread count, including uneven splits and counter wrap-around.
+
+use aes::{
+    ecb_decrypt, ecb_encrypt, parallel, BlockCipher, CbcDecryptor, CbcEncryptor, Ctr, Endianness, ModeError, Padding,
+    AES128, AES192,
+};
+
+const THREADS: [usize; 5] = [1, 2, 3, 4, 7];
+// lengths around block and split boundaries
+const LENGTHS: [usize; 8] = [0, 1, 15, 16, 17, 48, 100, 16 * 1000 + 5];
+
+fn sample(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 131 + 17) as u8).collect()
+}
+
+#[test]
+fn ecb_matches_serial() {
+    let aes = AES128::new([0x2b; 16]);
+    for len in LENGTHS {
+        let plaintext = sample(len);
+        let serial = ecb_encrypt(&aes, &plaintext);
+        for threads in THREADS {
+            assert_eq!(parallel::ecb_encrypt(&aes, &plaintext, threads), serial, "{} bytes, {} threads", len, threads);
+            assert_eq!(parallel::ecb_decrypt(&aes, &serial, threads), ecb_decrypt(&aes, &serial));
+        }
+    }
+}
+
+#[test]
+fn ctr_matches_serial() {
+    let aes = AES192::new([0x7e; 24]);
+    let initial_block = sample(16);
+    for len in LENGTHS {
+        let plaintext = sample(len);
+        for (counter_bytes, endianness) in [(16, Endianness::Big), (4, Endianness::Big), (8, Endianness::Little)] {
+            let serial = Ctr::new(&aes, &initial_block, counter_bytes, endianness).update(&plaintext);
+            for threads in THREADS {
+                let threaded = parallel::ctr_xor(&aes, &initial_block, counter_bytes, endianness, &plaintext, threads);
+                assert_eq!(threaded, serial, "{} bytes, {}-byte counter, {} threads", len, counter_bytes, threads);
+            }
+        }
+    }
+}
+
+#[test]
+fn ctr_workers_carry_and_wrap_the_counter() {
+    let aes = AES128::new([0x11; 16]);
+    let plaintext = sample(16 * 600);
+    // a 2-byte counter close to 0xffff wraps to 0 part-way through, a big-endian 4-byte one
+    // carries into its upper bytes, and the little-endian one carries the other way
+    let cases = [
+        ([0xaa; 14].iter().chain(&[0xff, 0x00]).copied().collect::<Vec<u8>>(), 2, Endianness::Big),
+        ([0xaa; 12].iter().chain(&[0x00, 0x00, 0xfe, 0xf0]).copied().collect(), 4, Endianness::Big),
+        ([0xaa; 8].iter().chain(&[0xf0, 0xfe, 0xff, 0, 0, 0, 0, 0]).copied().collect(), 8, Endianness::Little),
+    ];
+    for (initial_block, counter_bytes, endianness) in cases {
+        let serial = Ctr::new(&aes, &initial_block, counter_bytes, endianness).update(&plaintext);
+        for threads in THREADS {
+            assert_eq!(parallel::ctr_xor(&aes, &initial_block, counter_bytes, endianness, &plaintext, threads), serial);
+        }
+    }
+}
+
+#[test]
+fn cbc_decrypt_matches_serial() {
+    let aes = AES128::new([0x5c; 16]);
+    let iv = sample(16);
+    for len in LENGTHS {
+        let plaintext = sample(len);
+        let mut encryptor = CbcEncryptor::new(&aes, &iv, Padding::Pkcs7);
+        let mut ciphertext = encryptor.update(&plaintext);
+        ciphertext.extend(encryptor.finalize().unwrap());
+
+        for threads in THREADS {
+            assert_eq!(parallel::cbc_decrypt(&aes, &iv, &ciphertext, Padding::Pkcs7, threads).unwrap(), plaintext);
+
+            // without padding, the raw blocks come back as the serial decryptor gives them
+            let mut decryptor = CbcDecryptor::new(&aes, &iv, Padding::None);
+            let serial = decryptor.update(&ciphertext);
+            assert_eq!(parallel::cbc_decrypt(&aes, &iv, &ciphertext, Padding::None, threads).unwrap(), serial);
+        }
+    }
+}
+
+#[test]
+fn cbc_decrypt_reports_the_same_errors() {
+    let aes = AES128::new([0x5c; 16]);
+    let iv = [0u8; 16];
+    assert_eq!(parallel::cbc_decrypt(&aes, &iv, &[0u8; 20], Padding::None, 4), Err(ModeError::PartialBlock));
+    assert_eq!(parallel::cbc_decrypt(&aes, &iv, &[], Padding::Pkcs7, 4), Err(ModeError::PartialBlock));
+    assert_eq!(parallel::cbc_decrypt(&aes, &iv, &[], Padding::None, 4), Ok(Vec::new()));
+
+    // C2 = E(C1) decrypts to P2 = D(C2) ^ C1 = all zeros, which is not valid padding
+    let c1 = sample(16);
+    let mut c2 = c1.clone();
+    aes.encrypt_block(&mut c2);
+    let ciphertext = [c1, c2].concat();
+    assert_eq!(parallel::cbc_decrypt(&aes, &iv, &ciphertext, Padding::Pkcs7, 3), Err(ModeError::InvalidPadding));
+}
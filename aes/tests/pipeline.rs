@@ -0,0 +1,76 @@
+//! Test: the buffered Kattis pipeline assembles blocks across short reads, and the threaded
+//! path matches the serial one.
+
+use std::io::{self, Read};
+
+use aes::pipeline::{ecb_encrypt_stream, CHUNK_SIZE};
+use aes::{aes_for_key, ecb_encrypt, AES128, AES256};
+
+// Hands out 1 to 7 bytes per read, with an occasional `Interrupted` error in between
+struct TrickleReader<'a> {
+    data: &'a [u8],
+    reads: usize,
+}
+
+impl Read for TrickleReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads += 1;
+        if self.reads.is_multiple_of(11) {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
+        let n = (self.reads % 7 + 1).min(buf.len()).min(self.data.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+fn trickle(data: &[u8]) -> TrickleReader<'_> {
+    TrickleReader { data, reads: 0 }
+}
+
+fn sample(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+#[test]
+fn short_reads_still_produce_every_block() {
+    let aes = AES128::new([0x2b; 16]);
+    for len in [0, 15, 16, 17, 100, 4096 + 3] {
+        let plaintext = sample(len);
+        let mut output = Vec::new();
+        ecb_encrypt_stream(&aes, &mut trickle(&plaintext), &mut output, 1).unwrap();
+        assert_eq!(output, ecb_encrypt(&aes, &plaintext), "{} bytes", len);
+        assert_eq!(output.len(), len / 16 * 16);
+    }
+}
+
+#[test]
+fn threads_match_the_serial_path_across_chunks() {
+    let aes = AES256::new([0x5a; 32]);
+    let plaintext = sample(3 * CHUNK_SIZE + 1000);
+    let mut serial = Vec::new();
+    ecb_encrypt_stream(&aes, &mut &plaintext[..], &mut serial, 1).unwrap();
+    assert_eq!(serial, ecb_encrypt(&aes, &plaintext));
+
+    for threads in [2, 3, 8] {
+        let mut threaded = Vec::new();
+        ecb_encrypt_stream(&aes, &mut trickle(&plaintext), &mut threaded, threads).unwrap();
+        assert!(threaded == serial, "{} threads", threads);
+    }
+}
+
+#[test]
+fn kattis_mode_reads_the_key_across_short_reads() {
+    let key = [0x11u8; 16];
+    let plaintext = sample(1000);
+    let input = [&key[..], &plaintext].concat();
+    let expected = ecb_encrypt(&AES128::new(key), &plaintext);
+
+    for args in [vec!["--kattis"], vec!["--kattis", "128", "--threads", "4"]] {
+        let args: Vec<String> = args.into_iter().map(String::from).collect();
+        let mut output = Vec::new();
+        aes::cli::run(&args, &aes_for_key, &mut trickle(&input), &mut output).unwrap();
+        assert_eq!(output, expected, "{:?}", args);
+    }
+}
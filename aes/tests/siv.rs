@@ -0,0 +1,79 @@
+//! Test: AES-SIV against the RFC 5297 Appendix A vectors.
+
+use aes::{AuthError, Siv, AES128, AES256};
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+// Splits a 256-bit SIV key into the CMAC and CTR halves
+fn aes_siv_256(key: &str) -> Siv<AES128> {
+    let key = hex(key);
+    Siv::new(AES128::new(key[..16].try_into().unwrap()), AES128::new(key[16..].try_into().unwrap()))
+}
+
+#[test]
+fn a1_deterministic() {
+    let siv = aes_siv_256("fffefdfc fbfaf9f8 f7f6f5f4 f3f2f1f0 f0f1f2f3 f4f5f6f7 f8f9fafb fcfdfeff");
+    let ad = hex("10111213 14151617 18191a1b 1c1d1e1f 20212223 24252627");
+    let plaintext = hex("11223344 55667788 99aabbcc ddee");
+
+    let sealed = siv.seal(&[&ad], &plaintext);
+    assert_eq!(sealed, hex("85632d07 c6e8f37f 950acd32 0a2ecc93 40c02b96 90c4dc04 daef7f6a fe5c"));
+    assert_eq!(siv.open(&[&ad], &sealed), Ok(plaintext));
+}
+
+#[test]
+fn a2_nonce_based() {
+    let siv = aes_siv_256("7f7e7d7c 7b7a7978 77767574 73727170 40414243 44454647 48494a4b 4c4d4e4f");
+    let ad1 = hex("00112233 44556677 8899aabb ccddeeff deaddada deaddada ffeeddcc bbaa9988 77665544 33221100");
+    let ad2 = hex("10203040 50607080 90a0");
+    let nonce = hex("09f91102 9d74e35b d84156c5 635688c0");
+    let plaintext = hex("74686973 20697320 736f6d65 20706c61 696e7465 78742074 6f20656e 63727970
+                         74207573 696e6720 5349562d 414553");
+    let ad: [&[u8]; 3] = [&ad1, &ad2, &nonce];
+
+    let sealed = siv.seal(&ad, &plaintext);
+    assert_eq!(
+        sealed,
+        hex("7bdb6e3b 432667eb 06f4d14b ff2fbd0f cb900f2f ddbe4043 26601965 c889bf17
+             dba77ceb 094fa663 b7a3f748 ba8af829 ea64ad54 4a272e9c 485b62a3 fd5c0d")
+    );
+    assert_eq!(siv.open(&ad, &sealed), Ok(plaintext));
+}
+
+#[test]
+fn aes_siv_512_and_empty_inputs() {
+    // 512-bit key = two AES-256 keys; cross-checked against the Python cryptography package
+    let key: Vec<u8> = (0..64).collect();
+    let siv = Siv::new(AES256::new(key[..32].try_into().unwrap()), AES256::new(key[32..].try_into().unwrap()));
+
+    assert_eq!(siv.seal(&[], b""), hex("d4fc53b9c44c2aeea87bfb8c983b136c"));
+    let sealed = siv.seal(&[b"ad"], b"a 256-bit key pair");
+    assert_eq!(sealed, hex("76ddd14ee3e2fc10e88603b633498727c13844930731493eda30b6e584d367375780"));
+    assert_eq!(siv.open(&[b"ad"], &sealed), Ok(b"a 256-bit key pair".to_vec()));
+}
+
+#[test]
+fn same_inputs_same_output_and_tampering_detected() {
+    let siv = aes_siv_256("7f7e7d7c 7b7a7978 77767574 73727170 40414243 44454647 48494a4b 4c4d4e4f");
+    let nonce = [0u8; 16];
+
+    // a repeated nonce only shows that the message repeated
+    let first = siv.seal(&[&nonce], b"attack at dawn");
+    assert_eq!(siv.seal(&[&nonce], b"attack at dawn"), first);
+    assert_ne!(siv.seal(&[&nonce], b"attack at dusk"), first);
+
+    for i in 0..first.len() {
+        let mut tampered = first.clone();
+        tampered[i] ^= 0x04;
+        assert_eq!(siv.open(&[&nonce], &tampered), Err(AuthError), "flip at byte {}", i);
+    }
+    // associated-data components are not interchangeable with their concatenation
+    assert_eq!(siv.open(&[&nonce[..8], &nonce[8..]], &first), Err(AuthError));
+    assert_eq!(siv.open(&[], &first), Err(AuthError));
+}
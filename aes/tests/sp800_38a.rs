@@ -0,0 +1,215 @@
+//! Test: NIST SP 800-38A Appendix F vectors for CBC, CFB-8, CFB-128, OFB and CTR,
+//! fed through the streaming API in chunks of several sizes.
+
+use aes::{
+    aes_128_ecb_encrypt, BlockCipher, CbcDecryptor, CbcEncryptor, CfbDecryptor, CfbEncryptor, CfbSegment, Ctr,
+    Endianness, ModeError, Ofb, Padding, AES128, AES192, AES256,
+};
+
+const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172a ae2d8a571e03ac9c9eb76fac45af8e51
+                         30c81c46a35ce411e5fbc1191a0a52ef f69f2445df4f9b17ad2b417be66c3710";
+const KEY_128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const KEY_192: &str = "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b";
+const KEY_256: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+const IV: &str = "000102030405060708090a0b0c0d0e0f";
+const INITIAL_COUNTER: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+
+// Chunk sizes that do and do not line up with the 16-byte block
+const CHUNK_SIZES: [usize; 5] = [1, 5, 16, 17, 64];
+
+fn hex(s: &str) -> Vec<u8> {
+    let digits: Vec<u8> = s.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+fn cipher_for(key: &[u8]) -> Box<dyn BlockCipher> {
+    match key.len() {
+        16 => Box::new(AES128::new(key.try_into().unwrap())),
+        24 => Box::new(AES192::new(key.try_into().unwrap())),
+        32 => Box::new(AES256::new(key.try_into().unwrap())),
+        n => panic!("no AES variant with a {}-byte key", n),
+    }
+}
+
+// Feeds `input` to `update` in `chunk`-sized pieces and collects the output
+fn chunked(input: &[u8], chunk: usize, update: impl FnMut(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    input.chunks(chunk).flat_map(update).collect()
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Cbc,
+    Cfb8,
+    Cfb128,
+    Ofb,
+    Ctr,
+}
+
+fn encrypt(mode: Mode, key: &[u8], input: &[u8], chunk: usize) -> Vec<u8> {
+    let cipher = cipher_for(key);
+    match mode {
+        Mode::Cbc => {
+            let mut enc = CbcEncryptor::new(cipher, &hex(IV), Padding::None);
+            let mut out = chunked(input, chunk, |p| enc.update(p));
+            out.extend(enc.finalize().unwrap());
+            out
+        }
+        Mode::Cfb8 | Mode::Cfb128 => {
+            let segment = if matches!(mode, Mode::Cfb8) { CfbSegment::Bits8 } else { CfbSegment::FullBlock };
+            let mut enc = CfbEncryptor::new(cipher, &hex(IV), segment);
+            let mut out = chunked(input, chunk, |p| enc.update(p));
+            out.extend(enc.finalize());
+            out
+        }
+        Mode::Ofb => {
+            let mut ofb = Ofb::new(cipher, &hex(IV));
+            chunked(input, chunk, |p| ofb.update(p))
+        }
+        Mode::Ctr => {
+            let mut ctr = Ctr::new(cipher, &hex(INITIAL_COUNTER), 16, Endianness::Big);
+            chunked(input, chunk, |p| ctr.update(p))
+        }
+    }
+}
+
+fn decrypt(mode: Mode, key: &[u8], input: &[u8], chunk: usize) -> Vec<u8> {
+    let cipher = cipher_for(key);
+    match mode {
+        Mode::Cbc => {
+            let mut dec = CbcDecryptor::new(cipher, &hex(IV), Padding::None);
+            let mut out = chunked(input, chunk, |p| dec.update(p));
+            out.extend(dec.finalize().unwrap());
+            out
+        }
+        Mode::Cfb8 | Mode::Cfb128 => {
+            let segment = if matches!(mode, Mode::Cfb8) { CfbSegment::Bits8 } else { CfbSegment::FullBlock };
+            let mut dec = CfbDecryptor::new(cipher, &hex(IV), segment);
+            chunked(input, chunk, |p| dec.update(p))
+        }
+        // OFB and CTR are their own inverse
+        Mode::Ofb | Mode::Ctr => encrypt(mode, key, input, chunk),
+    }
+}
+
+fn check(mode: Mode, key: &str, expected: &str) {
+    let key = hex(key);
+    let expected = hex(expected);
+    // CFB-8 vectors only cover the first 18 bytes
+    let plaintext = &hex(PLAINTEXT)[..expected.len()];
+
+    for chunk in CHUNK_SIZES {
+        assert_eq!(encrypt(mode, &key, plaintext, chunk), expected, "encryption, chunks of {}", chunk);
+        assert_eq!(decrypt(mode, &key, &expected, chunk), plaintext, "decryption, chunks of {}", chunk);
+    }
+}
+
+#[test]
+fn f2_cbc() {
+    check(Mode::Cbc, KEY_128, "7649abac8119b246cee98e9b12e9197d 5086cb9b507219ee95db113a917678b2
+                               73bed6b8e3c1743b7116e69e22229516 3ff1caa1681fac09120eca307586e1a7");
+    check(Mode::Cbc, KEY_192, "4f021db243bc633d7178183a9fa071e8 b4d9ada9ad7dedf4e5e738763f69145a
+                               571b242012fb7ae07fa9baac3df102e0 08b0e27988598881d920a9e64f5615cd");
+    check(Mode::Cbc, KEY_256, "f58c4c04d6e5f1ba779eabfb5f7bfbd6 9cfc4e967edb808d679f777bc6702c7d
+                               39f23369a9d9bacfa530e26304231461 b2eb05e2c39be9fcda6c19078c6a9d1b");
+}
+
+#[test]
+fn f3_cfb8() {
+    check(Mode::Cfb8, KEY_128, "3b79424c9c0dd436bace9e0ed4586a4f32b9");
+    check(Mode::Cfb8, KEY_192, "cda2521ef0a905ca44cd057cbf0d47a0678a");
+    check(Mode::Cfb8, KEY_256, "dc1f1a8520a64db55fcc8ac554844e889700");
+}
+
+#[test]
+fn f3_cfb128() {
+    check(Mode::Cfb128, KEY_128, "3b3fd92eb72dad20333449f8e83cfb4a c8a64537a0b3a93fcde3cdad9f1ce58b
+                                  26751f67a3cbb140b1808cf187a4f4df c04b05357c5d1c0eeac4c66f9ff7f2e6");
+    check(Mode::Cfb128, KEY_192, "cdc80d6fddf18cab34c25909c99a4174 67ce7f7f81173621961a2b70171d3d7a
+                                  2e1e8a1dd59b88b1c8e60fed1efac4c9 c05f9f9ca9834fa042ae8fba584b09ff");
+    check(Mode::Cfb128, KEY_256, "dc7e84bfda79164b7ecd8486985d3860 39ffed143b28b1c832113c6331e5407b
+                                  df10132415e54b92a13ed0a8267ae2f9 75a385741ab9cef82031623d55b1e471");
+}
+
+#[test]
+fn f4_ofb() {
+    check(Mode::Ofb, KEY_128, "3b3fd92eb72dad20333449f8e83cfb4a 7789508d16918f03f53c52dac54ed825
+                               9740051e9c5fecf64344f7a82260edcc 304c6528f659c77866a510d9c1d6ae5e");
+    check(Mode::Ofb, KEY_192, "cdc80d6fddf18cab34c25909c99a4174 fcc28b8d4c63837c09e81700c1100401
+                               8d9a9aeac0f6596f559c6d4daf59a5f2 6d9f200857ca6c3e9cac524bd9acc92a");
+    check(Mode::Ofb, KEY_256, "dc7e84bfda79164b7ecd8486985d3860 4febdc6740d20b3ac88f6ad82a4fb08d
+                               71ab47a086e86eedf39d1c5bba97c408 0126141d67f37be8538f5a8be740e484");
+}
+
+#[test]
+fn f5_ctr() {
+    check(Mode::Ctr, KEY_128, "874d6191b620e3261bef6864990db6ce 9806f66b7970fdff8617187bb9fffdff
+                               5ae4df3edbd5d35e5b4f09020db03eab 1e031dda2fbe03d1792170a0f3009cee");
+    check(Mode::Ctr, KEY_192, "1abc932417521ca24f2b0459fe7e6e0b 090339ec0aa6faefd5ccc2c6f4ce8e94
+                               1e36b26bd1ebc670d1bd1d665620abf7 4f78a7f6d29809585a97daec58c6b050");
+    check(Mode::Ctr, KEY_256, "601ec313775789a5b7a7f504bbf3d228 f443e3ca4d62b59aca84e990cacaf5c5
+                               2b0930daa23de94ce87017ba2d84988d dfc9c58db67aada613c2dd08457941a6");
+}
+
+#[test]
+fn ctr_counter_width_and_endianness() {
+    let key = [0x42u8; 16];
+    let aes = AES128::new(key);
+    // nonce aa.. with a 1-byte counter about to wrap: only the last byte may change
+    let mut initial = [0xaau8; 16];
+    initial[15] = 0xff;
+
+    let mut ctr = Ctr::new(&aes, &initial, 1, Endianness::Big);
+    let keystream = ctr.update(&[0u8; 32]);
+
+    let mut wrapped = initial;
+    wrapped[15] = 0x00;
+    let expected: Vec<u8> = [initial, wrapped].concat();
+    assert_eq!(keystream, aes_128_ecb_encrypt(&expected, &key));
+
+    // little-endian 64-bit counter after an 8-byte nonce: the carry moves towards the end
+    let mut initial = [0u8; 16];
+    initial[8] = 0xff;
+    let mut ctr = Ctr::new(&aes, &initial, 8, Endianness::Little);
+    let keystream = ctr.update(&[0u8; 32]);
+
+    let mut next = initial;
+    next[8] = 0x00;
+    next[9] = 0x01;
+    let expected: Vec<u8> = [initial, next].concat();
+    assert_eq!(keystream, aes_128_ecb_encrypt(&expected, &key));
+}
+
+#[test]
+fn cbc_pkcs7_round_trip_and_errors() {
+    let aes = AES128::new([7u8; 16]);
+    let iv = [9u8; 16];
+
+    for len in [0, 1, 15, 16, 17, 40] {
+        let plaintext: Vec<u8> = (0..len as u8).collect();
+        let mut enc = CbcEncryptor::new(&aes, &iv, Padding::Pkcs7);
+        let mut ciphertext = chunked(&plaintext, 7, |p| enc.update(p));
+        ciphertext.extend(enc.finalize().unwrap());
+        assert_eq!(ciphertext.len(), (len / 16 + 1) * 16, "padding always adds a block boundary");
+
+        let mut dec = CbcDecryptor::new(&aes, &iv, Padding::Pkcs7);
+        let mut decrypted = chunked(&ciphertext, 16, |p| dec.update(p));
+        decrypted.extend(dec.finalize().unwrap());
+        assert_eq!(decrypted, plaintext);
+    }
+
+    // flipping the last IV-side byte of the final block corrupts the padding
+    let mut enc = CbcEncryptor::new(&aes, &iv, Padding::Pkcs7);
+    let mut ciphertext = enc.update(b"sixteen byte msg");
+    ciphertext.extend(enc.finalize().unwrap());
+    ciphertext[15] ^= 0x01;
+    let mut dec = CbcDecryptor::new(&aes, &iv, Padding::Pkcs7);
+    dec.update(&ciphertext);
+    assert_eq!(dec.finalize(), Err(ModeError::InvalidPadding));
+
+    let mut enc = CbcEncryptor::new(&aes, &iv, Padding::None);
+    enc.update(&[0u8; 20]);
+    assert_eq!(enc.finalize(), Err(ModeError::PartialBlock));
+}
@@ -0,0 +1,53 @@
+//! Bernstein's AES timing attack against a T-table victim on this host.
+//!
+//! Usage: cargo run --release --example bernstein -- [--samples N] [--in-process]
+//!
+//! Starts two victims on localhost TCP (or in this process with `--in-process`): one under an
+//! all-zero key for the study profile and one under a secret key. Prints, for every key byte,
+//! the best guesses and where the real byte ranked.
+
+use std::net::TcpListener;
+use std::thread;
+
+use cryptanalysis::timing::{correlate, remaining_key_bits, serve, LocalVictim, Profile, RemoteVictim, TimingOracle};
+
+const KNOWN_KEY: [u8; 16] = [0; 16];
+const SECRET_KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+fn victim(key: [u8; 16], in_process: bool) -> std::io::Result<Box<dyn TimingOracle>> {
+    if in_process {
+        return Ok(Box::new(LocalVictim::new(key)));
+    }
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || serve(&listener, key));
+    Ok(Box::new(RemoteVictim::connect(address)?))
+}
+
+fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let in_process = args.iter().any(|arg| arg == "--in-process");
+    let samples = match args.iter().position(|arg| arg == "--samples") {
+        Some(i) => args.get(i + 1).and_then(|n| n.parse().ok()).expect("--samples needs a number"),
+        None => 1 << 20,
+    };
+
+    println!("{} samples per profile, victims {}", samples, if in_process { "in-process" } else { "on localhost TCP" });
+    let study = Profile::collect(victim(KNOWN_KEY, in_process)?.as_mut(), samples, 1)?;
+    let attack = Profile::collect(victim(SECRET_KEY, in_process)?.as_mut(), samples, 2)?;
+    println!("outliers dropped: {} study, {} attack\n", study.discarded, attack.discarded);
+
+    let rankings = correlate(&study, &KNOWN_KEY, &attack);
+    for (position, ranking) in rankings.iter().enumerate() {
+        let best: Vec<String> = ranking.candidates[..4].iter().map(|(k, _)| format!("{:02x}", k)).collect();
+        println!(
+            "byte {:2}: best {}  real {:02x} ranked {}",
+            position,
+            best.join(" "),
+            SECRET_KEY[position],
+            ranking.rank_of(SECRET_KEY[position]) + 1
+        );
+    }
+    println!("\nkey search left: 2^{:.1} of 2^128", remaining_key_bits(&rankings, &SECRET_KEY));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Correlation power analysis of simulated first-round AES-128 traces.
+//!
+//! Usage: cargo run --release --example cpa -- [--traces N] [--noise SIGMA] [--model hw|hd]
+//!        [--masked] [--save FILE | --load FILE]
+//!
+//! Generates traces under a fixed secret key, of `aes::AES128` or with `--masked` of
+//! `aes_v2::MaskedAES128` (or reads them back from a file saved by an earlier run), then
+//! prints the rank of every key byte as traces are added and the key CPA recovers from all
+//! of them.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use cryptanalysis::cpa::{rank_by_trace_count, Cpa, LeakageModel, TraceGenerator, TraceSet};
+
+const SECRET_KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|arg| arg == name).map(|i| args.get(i + 1).map(String::as_str).expect("missing value"))
+}
+
+fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let traces: usize = option(&args, "--traces").map_or(1000, |n| n.parse().expect("--traces needs a number"));
+    let noise: f64 = option(&args, "--noise").map_or(2.0, |s| s.parse().expect("--noise needs a number"));
+    let model = match option(&args, "--model").unwrap_or("hw") {
+        "hw" => LeakageModel::HammingWeight,
+        "hd" => LeakageModel::HammingDistance,
+        other => panic!("unknown model {}, expected hw or hd", other),
+    };
+
+    let masked = args.iter().any(|arg| arg == "--masked");
+
+    let set = match option(&args, "--load") {
+        Some(path) => TraceSet::load(&mut BufReader::new(File::open(path)?))?,
+        None if masked => TraceGenerator::masked(SECRET_KEY, model, noise, 1).generate(traces),
+        None => TraceGenerator::new(SECRET_KEY, model, noise, 1).generate(traces),
+    };
+    if let Some(path) = option(&args, "--save") {
+        set.save(&mut BufWriter::new(File::create(path)?))?;
+        println!("saved {} traces to {}", set.traces.len(), path);
+    }
+    println!("{} traces, {} samples each, model {:?}\n", set.traces.len(), set.samples_per_trace(), model);
+
+    let total = set.traces.len();
+    let counts: Vec<usize> =
+        std::iter::successors(Some(10), |&n| Some(n * 2)).take_while(|&n| n < total).chain([total]).collect();
+    println!("{:>7}  rank of each key byte (0 = recovered)", "traces");
+    for (count, ranks) in rank_by_trace_count(&set, model, &SECRET_KEY, &counts) {
+        let ranks: Vec<String> = ranks.iter().map(|r| format!("{:3}", r)).collect();
+        println!("{:>7}  {}", count, ranks.join(" "));
+    }
+
+    let mut cpa = Cpa::new(model, set.samples_per_trace());
+    set.traces.iter().for_each(|trace| cpa.add(trace));
+    let key = cpa.best_key();
+    let recovered = key.iter().zip(SECRET_KEY).filter(|&(&a, b)| a == b).count();
+    let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    println!("\nbest key {}  ({}/16 bytes right)", hex, recovered);
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Constant-time tests of the AES backends, CBC padding removal and the smpc array equalities.
+//!
+//! Usage: cargo run --release --example dudect -- [--measurements N]
+//!
+//! Each target is timed on a fixed input against random ones (see `cryptanalysis::dudect`)
+//! and gets one line saying whether a timing difference showed. The garbled-circuit targets
+//! are far slower per call and get a twentieth of the measurements.
+
+use aes::{BlockCipher, CbcDecryptor, CbcEncryptor, Padding, TTableAES128};
+use cryptanalysis::dudect::{check, Class, Report};
+use cryptanalysis::rng::Rng;
+use smpc::{leaky_array_equality, oblivious_array_equality, GarbledNandGate, WireLabel, WireLabels};
+
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+const IV: [u8; 16] = [0; 16];
+
+// Encrypting the all-zero block against random blocks
+fn block_cipher(cipher: &dyn BlockCipher, measurements: usize) -> Report {
+    let input = |class: Class, rng: &mut Rng| match class {
+        Class::Fixed => [0u8; 16],
+        Class::Random => rng.bytes(),
+    };
+    check(measurements, 1, input, |block: &[u8; 16]| {
+        let mut block = *block;
+        cipher.encrypt_block(&mut block);
+        std::hint::black_box(block);
+    })
+}
+
+// A last block with valid padding against random ones, which almost never have any
+fn cbc_padding(measurements: usize) -> Report {
+    let aes = aes::AES128::new(KEY);
+    let mut encryptor = CbcEncryptor::new(&aes, &IV, Padding::Pkcs7);
+    let mut valid = encryptor.update(&[]);
+    valid.extend(encryptor.finalize().unwrap());
+    let valid: [u8; 16] = valid.try_into().unwrap();
+
+    let input = |class: Class, rng: &mut Rng| match class {
+        Class::Fixed => valid,
+        Class::Random => rng.bytes(),
+    };
+    check(measurements, 2, input, |ciphertext: &[u8; 16]| {
+        let mut decryptor = CbcDecryptor::new(&aes, &IV, Padding::Pkcs7);
+        decryptor.update(ciphertext);
+        let _ = std::hint::black_box(decryptor.finalize());
+    })
+}
+
+// Alice holds all ones; Bob holds the same array or random bits
+struct Equality {
+    alice_labels: Vec<WireLabels>,
+    bob_labels: Vec<WireLabels>,
+    alice_inputs: Vec<WireLabel>,
+    output_labels: WireLabels,
+}
+
+impl Equality {
+    fn new(n: usize) -> Self {
+        let gates: Vec<GarbledNandGate> = (0..n).map(|_| GarbledNandGate::new()).collect();
+        let alice_labels: Vec<WireLabels> = gates.iter().map(|gate| gate.x_labels.clone()).collect();
+        let bob_labels = gates.iter().map(|gate| gate.y_labels.clone()).collect();
+        let alice_inputs = alice_labels.iter().map(|labels| labels.one.clone()).collect();
+        let output_labels = gates[0].z_labels.clone();
+        Self { alice_labels, bob_labels, alice_inputs, output_labels }
+    }
+
+    fn bob_inputs(&self, class: Class, rng: &mut Rng) -> Vec<WireLabel> {
+        let bit = |rng: &mut Rng| class == Class::Fixed || rng.next_u64() >> 63 == 1;
+        self.bob_labels.iter().map(|labels| if bit(rng) { labels.one.clone() } else { labels.zero.clone() }).collect()
+    }
+
+    fn leaky(&self, bob_inputs: &[WireLabel]) {
+        let result = leaky_array_equality(
+            &self.alice_labels,
+            &self.bob_labels,
+            &self.alice_inputs,
+            bob_inputs,
+            &self.output_labels,
+        );
+        std::hint::black_box(result);
+    }
+
+    fn oblivious(&self, bob_inputs: &[WireLabel]) {
+        let result = oblivious_array_equality(
+            &self.alice_labels,
+            &self.bob_labels,
+            &self.alice_inputs,
+            bob_inputs,
+            &self.output_labels,
+        );
+        std::hint::black_box(result);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let measurements = match args.iter().position(|arg| arg == "--measurements") {
+        Some(i) => args.get(i + 1).and_then(|n| n.parse().ok()).expect("--measurements needs a number"),
+        None => 100_000,
+    };
+
+    let backends: [(&str, Box<dyn BlockCipher>); 6] = [
+        ("aes::AES128", Box::new(aes::AES128::new(KEY))),
+        ("aes::AES128 portable", Box::new(aes::AES128::new_portable(KEY))),
+        ("aes::TTableAES128", Box::new(TTableAES128::new(KEY))),
+        ("aes_v2::AES128", Box::new(aes_v2::AES128::new(KEY))),
+        ("aes_v2::BitslicedAES128", Box::new(aes_v2::BitslicedAES128::new(KEY))),
+        ("aes_v2::MaskedAES128", Box::new(aes_v2::MaskedAES128::new(KEY))),
+    ];
+    for (name, cipher) in &backends {
+        println!("{:30} {}", name, block_cipher(cipher.as_ref(), measurements));
+    }
+    println!("{:30} {}", "CBC PKCS#7 removal", cbc_padding(measurements));
+
+    let equality = Equality::new(32);
+    let input = |class, rng: &mut Rng| equality.bob_inputs(class, rng);
+    let leaky = check(measurements / 20, 3, input, |bob| equality.leaky(bob));
+    println!("{:30} {}", "smpc leaky array_equality", leaky);
+    let oblivious = check(measurements / 20, 4, input, |bob| equality.oblivious(bob));
+    println!("{:30} {}", "smpc oblivious array_equality", oblivious);
+}
@@ -0,0 +1,101 @@
+//! Piret-Quisquater differential fault analysis of AES-128.
+//!
+//! A single-byte fault at the start of round 9 reaches MixColumns alone in its column, which
+//! turns a difference f in row i into the differences (2f, f, f, 3f) rotated down by i. The
+//! last round maps those four bytes to four ciphertext bytes through SubBytes, ShiftRows and
+//! four bytes of the last round key, so for each fault row and value, undoing the last round
+//! for each ciphertext byte gives an equation in one key byte. A pair narrows that column of
+//! the key from 2^32 values to about 2^10, and two pairs usually leave one.
+//!
+//! A fault at the start of round 8 spreads to one full column, and round 9 then leaves one
+//! active byte in every column, so the same equations hold in all four columns at once: two
+//! faulty ciphertexts give the whole last round key, which is the Piret-Quisquater result.
+
+use std::collections::HashSet;
+
+use aes::gf256::gf256_mul;
+use aes::key_schedule::aes128_key_from_round_key;
+use aes::sbox::AES_INV_SBOX;
+
+/// A correct ciphertext and a faulty one for the same plaintext and key.
+pub type FaultyPair = ([u8; 16], [u8; 16]);
+
+/// Recovers the AES-128 cipher key from pairs faulted in round 8 or 9. Returns `None` if some
+/// column of the last round key is left ambiguous, or if no key fits every pair (a fault in
+/// another round, or more than one byte).
+pub fn recover_key(pairs: &[FaultyPair]) -> Option<[u8; 16]> {
+    let round_key = last_round_key(pairs)?;
+    Some(aes128_key_from_round_key(&round_key, 10))
+}
+
+/// Recovers round key 10, intersecting the candidates each pair leaves for each column.
+pub fn last_round_key(pairs: &[FaultyPair]) -> Option<[u8; 16]> {
+    let mut round_key = [0u8; 16];
+    for column in 0..4 {
+        let mut candidates: Option<HashSet<[u8; 4]>> = None;
+        for (correct, faulty) in pairs {
+            // a fault in round 9 only shows in one column and says nothing about the others
+            if positions(column).iter().all(|&p| correct[p] == faulty[p]) {
+                continue;
+            }
+            let new = column_candidates(correct, faulty, column);
+            candidates = Some(match candidates {
+                None => new,
+                Some(old) => old.intersection(&new).copied().collect(),
+            });
+        }
+
+        let candidates = candidates?;
+        if candidates.len() != 1 {
+            return None;
+        }
+        let key_bytes = candidates.into_iter().next().unwrap();
+        for (&position, byte) in positions(column).iter().zip(key_bytes) {
+            round_key[position] = byte;
+        }
+    }
+    Some(round_key)
+}
+
+/// The values of the last round key bytes over `column` (in the order of `positions`) that
+/// explain the pair as a single-byte fault in that column of the round 9 MixColumns input.
+pub fn column_candidates(correct: &[u8; 16], faulty: &[u8; 16], column: usize) -> HashSet<[u8; 4]> {
+    // keys[row][d] lists the key bytes k with InvSBox(c ^ k) ^ InvSBox(c' ^ k) = d
+    let keys: [Vec<Vec<u8>>; 4] = std::array::from_fn(|row| {
+        let position = positions(column)[row];
+        let (c, c_faulty) = (correct[position], faulty[position]);
+        let mut keys = vec![Vec::new(); 256];
+        for k in 0..=255u8 {
+            let d = AES_INV_SBOX[(c ^ k) as usize] ^ AES_INV_SBOX[(c_faulty ^ k) as usize];
+            keys[d as usize].push(k);
+        }
+        keys
+    });
+
+    let mut candidates = HashSet::new();
+    for fault_row in 0..4 {
+        for f in 1..=255u8 {
+            let [k0, k1, k2, k3] =
+                std::array::from_fn(|row| &keys[row][gf256_mul(MIX[(fault_row + 4 - row) % 4], f) as usize]);
+            for &a in k0 {
+                for &b in k1 {
+                    for &c in k2 {
+                        for &d in k3 {
+                            candidates.insert([a, b, c, d]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// Where the bytes of column `column` of the last round input end up in the ciphertext,
+/// row by row: ShiftRows moves row r left by r columns.
+pub fn positions(column: usize) -> [usize; 4] {
+    std::array::from_fn(|row| 4 * ((column + 4 - row) % 4) + row)
+}
+
+// first row of the MixColumns matrix
+const MIX: [u8; 4] = [2, 3, 1, 1];
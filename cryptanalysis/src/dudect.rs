@@ -0,0 +1,178 @@
+//! Statistical constant-time testing in the style of dudect (Reparaz, Balasch, Verbauwhede).
+//!
+//! The target runs on inputs of two classes, one fixed input and fresh random ones, in random
+//! order, and every call is timed with `timing::cycles`. If the running time does not depend
+//! on the input, both classes have the same timing distribution and Welch's t-test between
+//! them stays small. Interrupts and cache misses give timings long upper tails that can drown
+//! a shift of the bulk, so the test is repeated on only the timings below each of `CROPS`
+//! percentiles as well. Any |t| over `THRESHOLD` is a leak.
+//!
+//! No leak detected only means none showed at this number of measurements on this machine.
+
+use std::fmt;
+
+use crate::rng::Rng;
+use crate::timing::cycles;
+
+/// |t| over which the two classes are taken to time differently (dudect's threshold).
+pub const THRESHOLD: f64 = 4.5;
+
+/// Cropped tests; test i keeps the timings below percentile 1 - 0.5^(10 (i + 1) / CROPS).
+pub const CROPS: usize = 100;
+
+/// The input class of one measurement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Class {
+    Fixed,
+    Random,
+}
+
+/// One timed call: the class of its input and the cycles it took.
+pub type Measurement = (Class, u64);
+
+/// Running means and variances of the two classes (Welford) for Welch's t-test.
+#[derive(Clone, Debug, Default)]
+pub struct Welch {
+    count: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    pub fn push(&mut self, class: Class, x: f64) {
+        let c = class as usize;
+        self.count[c] += 1.0;
+        let delta = x - self.mean[c];
+        self.mean[c] += delta / self.count[c];
+        self.m2[c] += delta * (x - self.mean[c]);
+    }
+
+    pub fn count(&self) -> usize {
+        (self.count[0] + self.count[1]) as usize
+    }
+
+    pub fn mean(&self, class: Class) -> f64 {
+        self.mean[class as usize]
+    }
+
+    /// Welch's t statistic, fixed minus random; 0 until each class has two values.
+    pub fn t(&self) -> f64 {
+        if self.count.iter().any(|&n| n < 2.0) {
+            return 0.0;
+        }
+        let variance = |c: usize| self.m2[c] / (self.count[c] - 1.0) / self.count[c];
+        let difference = self.mean[0] - self.mean[1];
+        let error = (variance(0) + variance(1)).sqrt();
+        if error > 0.0 {
+            difference / error
+        } else if difference == 0.0 {
+            0.0
+        } else {
+            difference.signum() * f64::INFINITY
+        }
+    }
+}
+
+/// The outcome of a constant-time test.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub measurements: usize,
+    /// Mean cycles of the fixed and the random class, over all measurements.
+    pub means: [f64; 2],
+    /// The t statistic of largest magnitude over the uncropped and cropped tests.
+    pub t: f64,
+    /// The crop that gave it, as the timing limit in cycles, or None for the uncropped test.
+    pub crop: Option<u64>,
+    /// Measurements in the test that gave it.
+    pub tested: usize,
+}
+
+impl Report {
+    pub fn leak_detected(&self) -> bool {
+        self.t.abs() > THRESHOLD
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.leak_detected() { "leak detected" } else { "no leak detected" };
+        write!(f, "{}: max |t| = {:.2}", verdict, self.t.abs())?;
+        match self.crop {
+            Some(limit) => write!(f, " on the {} timings under {} cycles", self.tested, limit)?,
+            None => write!(f, " on all {} timings", self.tested)?,
+        }
+        write!(f, " (mean fixed {:.1}, random {:.1} cycles)", self.means[0], self.means[1])
+    }
+}
+
+/// Times `count` calls of `target`, each on an input made by `input` for a random class.
+/// The inputs are all made before the first call so that making them is never timed.
+pub fn measure<I>(
+    count: usize,
+    seed: u64,
+    mut input: impl FnMut(Class, &mut Rng) -> I,
+    mut target: impl FnMut(&I),
+) -> Vec<Measurement> {
+    let mut rng = Rng::new(seed);
+    let inputs: Vec<(Class, I)> = (0..count)
+        .map(|_| {
+            let class = if rng.next_u64() >> 63 == 0 { Class::Fixed } else { Class::Random };
+            (class, input(class, &mut rng))
+        })
+        .collect();
+
+    // warm the caches and branch predictors up on the first few inputs
+    for (_, input) in inputs.iter().take(count / 100) {
+        target(std::hint::black_box(input));
+    }
+    inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = cycles();
+            target(std::hint::black_box(input));
+            (*class, cycles().wrapping_sub(start))
+        })
+        .collect()
+}
+
+/// Welch's t-test between the classes over all measurements and under every crop.
+pub fn analyse(measurements: &[Measurement]) -> Report {
+    let mut sorted: Vec<u64> = measurements.iter().map(|&(_, time)| time).collect();
+    sorted.sort_unstable();
+    let limits: Vec<u64> = (0..CROPS)
+        .map(|i| {
+            let percentile = 1.0 - 0.5f64.powf(10.0 * (i + 1) as f64 / CROPS as f64);
+            sorted.get((percentile * sorted.len() as f64) as usize).copied().unwrap_or(u64::MAX)
+        })
+        .collect();
+
+    let mut all = Welch::default();
+    let mut cropped = vec![Welch::default(); CROPS];
+    for &(class, time) in measurements {
+        all.push(class, time as f64);
+        for (welch, &limit) in cropped.iter_mut().zip(&limits) {
+            if time < limit {
+                welch.push(class, time as f64);
+            }
+        }
+    }
+
+    let mut report = Report {
+        measurements: measurements.len(),
+        means: [all.mean(Class::Fixed), all.mean(Class::Random)],
+        t: all.t(),
+        crop: None,
+        tested: all.count(),
+    };
+    for (welch, &limit) in cropped.iter().zip(&limits) {
+        if welch.t().abs() > report.t.abs() {
+            (report.t, report.crop, report.tested) = (welch.t(), Some(limit), welch.count());
+        }
+    }
+    report
+}
+
+/// `measure` and then `analyse`.
+pub fn check<I>(count: usize, seed: u64, input: impl FnMut(Class, &mut Rng) -> I, target: impl FnMut(&I)) -> Report {
+    analyse(&measure(count, seed, input, target))
+}
@@ -1,4 +1,10 @@
 pub mod vigenere;
 pub mod substitution;
+pub mod square;
+pub mod dfa;
+pub mod timing;
+pub mod cpa;
+pub mod dudect;
+pub mod rng;
 
 pub use vigenere::*;
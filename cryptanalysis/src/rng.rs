@@ -0,0 +1,4 @@
+//! The seeded generator behind every random choice of the attacks, simulations and tests,
+//! shared with the `aes` crates.
+
+pub use aes::rng::Rng;
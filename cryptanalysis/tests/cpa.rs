@@ -0,0 +1,98 @@
+//! Test: CPA recovers the whole AES-128 key from noisy simulated traces under both leakage
+//! models, key ranks fall as traces are added, and trace files read back unchanged. Against
+//! the masked AES-128 the same attack finds nothing.
+
+use std::io::{self, Cursor};
+
+use cryptanalysis::cpa::{rank_by_trace_count, Cpa, LeakageModel, TraceGenerator, TraceSet};
+
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+fn recover(model: LeakageModel, traces: usize) -> [u8; 16] {
+    let set = TraceGenerator::new(KEY, model, 1.0, 7).generate(traces);
+    let mut cpa = Cpa::new(model, set.samples_per_trace());
+    set.traces.iter().for_each(|trace| cpa.add(trace));
+    cpa.best_key()
+}
+
+#[test]
+fn recovers_the_key_from_hamming_weight_leakage() {
+    assert_eq!(recover(LeakageModel::HammingWeight, 500), KEY);
+}
+
+#[test]
+fn recovers_the_key_from_hamming_distance_leakage() {
+    assert_eq!(recover(LeakageModel::HammingDistance, 500), KEY);
+}
+
+#[test]
+fn masking_defeats_first_order_cpa() {
+    for model in [LeakageModel::HammingWeight, LeakageModel::HammingDistance] {
+        let set = TraceGenerator::masked(KEY, model, 1.0, 5).generate(2000);
+        let mut cpa = Cpa::new(model, set.samples_per_trace());
+        set.traces.iter().for_each(|trace| cpa.add(trace));
+        let ranks = cpa.key_ranks(&KEY);
+        let recovered = ranks.iter().filter(|&&rank| rank == 0).count();
+        assert!(recovered <= 1, "{:?}: {} bytes recovered, ranks {:?}", model, recovered, ranks);
+        // a random ranking puts the key byte at 127.5 on average
+        assert!(ranks.iter().sum::<usize>() > 16 * 50, "{:?}: ranks {:?}", model, ranks);
+    }
+    // the same number of unmasked traces is plenty
+    assert_eq!(recover(LeakageModel::HammingWeight, 2000), KEY);
+}
+
+#[test]
+fn ranks_improve_with_more_traces() {
+    let model = LeakageModel::HammingWeight;
+    let set = TraceGenerator::new(KEY, model, 3.0, 11).generate(2000);
+    // the counts come back sorted
+    let progression = rank_by_trace_count(&set, model, &KEY, &[2000, 5]);
+    assert_eq!(progression[0].0, 5);
+    assert_eq!(progression[1].0, 2000);
+    let (few, many) = (progression[0].1.iter().sum::<usize>(), progression[1].1.iter().sum::<usize>());
+    assert!(few > many, "{} traces: {:?}, all: {:?}", 5, progression[0].1, progression[1].1);
+    assert_eq!(progression[1].1, [0; 16]);
+}
+
+#[test]
+fn trace_files_round_trip() {
+    let set = TraceGenerator::new(KEY, LeakageModel::HammingWeight, 1.0, 3).generate(20);
+    let mut file = Vec::new();
+    set.save(&mut file).unwrap();
+    assert_eq!(file.len(), 8 + 12 + 20 * (32 + 16 * 4));
+    assert_eq!(TraceSet::load(&mut Cursor::new(&file)).unwrap(), set);
+
+    // the ciphertexts are the real ones
+    let mut block = set.traces[0].plaintext;
+    aes::BlockCipher::encrypt_block(&aes::AES128::new(KEY), &mut block);
+    assert_eq!(block, set.traces[0].ciphertext);
+}
+
+#[test]
+fn sets_the_format_cannot_hold_are_not_written() {
+    let mut set = TraceGenerator::new(KEY, LeakageModel::HammingWeight, 1.0, 3).generate(3);
+    set.traces[2].samples.pop();
+    let mut file = Vec::new();
+    assert_eq!(set.save(&mut file).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert!(file.is_empty());
+
+    set.traces.iter_mut().for_each(|trace| trace.samples = vec![0.0; (1 << 20) + 1]);
+    assert_eq!(set.save(&mut file).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    assert!(file.is_empty());
+}
+
+#[test]
+fn bad_trace_files_are_rejected() {
+    let err = TraceSet::load(&mut Cursor::new(b"NOTTRACE\x01\0\0\0")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let set = TraceGenerator::new(KEY, LeakageModel::HammingWeight, 1.0, 3).generate(2);
+    let mut file = Vec::new();
+    set.save(&mut file).unwrap();
+    file.truncate(file.len() - 1);
+    assert_eq!(TraceSet::load(&mut Cursor::new(&file)).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+
+    // a header claiming 2^32 - 1 samples per trace is refused before anything is allocated
+    file[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(TraceSet::load(&mut Cursor::new(&file)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,77 @@
+//! Test: differential fault analysis recovers random AES-128 keys from a handful of simulated
+//! single-byte faults in round 8 or 9, and rejects faults it cannot explain.
+
+use aes::block_cipher::with_state;
+use aes::{BlockCipher, Fault, AES128};
+use cryptanalysis::dfa::{column_candidates, last_round_key, positions, recover_key, FaultyPair};
+use cryptanalysis::rng::Rng;
+
+// Encrypts a random plaintext once correctly and once with a fault in `round` at `position`
+fn faulty_pair(aes: &AES128, rng: &mut Rng, round: usize, position: usize) -> FaultyPair {
+    let plaintext = rng.bytes();
+    let mask = rng.next_u8().max(1);
+    let mut correct = plaintext;
+    aes.encrypt_block(&mut correct);
+    let mut faulty = plaintext;
+    with_state(&mut faulty, |state| aes.cipher_with_fault(state, Fault::new(round, position, mask)));
+    (correct, faulty)
+}
+
+#[test]
+fn two_round_8_faults_give_the_key() {
+    let mut rng = Rng::new(8);
+    for _ in 0..4 {
+        let key = rng.bytes();
+        let aes = AES128::new(key);
+        let mut pairs = Vec::new();
+        // two pairs almost always suffice, a few more make the test independent of luck
+        while recover_key(&pairs) != Some(key) {
+            assert!(pairs.len() < 4, "no unique key after {} faults", pairs.len());
+            let position = rng.next_u8() as usize % 16;
+            pairs.push(faulty_pair(&aes, &mut rng, 8, position));
+        }
+    }
+}
+
+#[test]
+fn round_9_faults_in_every_column_give_the_key() {
+    let mut rng = Rng::new(9);
+    let key = rng.bytes();
+    let aes = AES128::new(key);
+    // three faults per column of the round 9 MixColumns input, each from a random row,
+    // which ShiftRows moves there from `row` columns further right
+    let pairs: Vec<FaultyPair> = (0..12)
+        .map(|i| {
+            let row = rng.next_u8() as usize % 4;
+            faulty_pair(&aes, &mut rng, 9, 4 * ((i + row) % 4) + row)
+        })
+        .collect();
+    assert_eq!(recover_key(&pairs), Some(key));
+}
+
+#[test]
+fn the_right_column_key_is_always_a_candidate() {
+    // FIPS-197 Appendix A.1 key and its round key 10
+    let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    let k10 = [0xd0, 0x14, 0xf9, 0xa8, 0xc9, 0xee, 0x25, 0x89, 0xe1, 0x3f, 0x0c, 0xc8, 0xb6, 0x63, 0x0c, 0xa6];
+    let mut rng = Rng::new(10);
+    let (correct, faulty) = faulty_pair(&AES128::new_portable(key), &mut rng, 9, 6);
+
+    // position 6 is row 2 of column 1, which ShiftRows moves to column 3 before MixColumns
+    let candidates = column_candidates(&correct, &faulty, 3);
+    assert!(candidates.contains(&positions(3).map(|p| k10[p])));
+    assert!(candidates.len() < 1 << 12, "{} candidates", candidates.len());
+    for column in [0, 1, 2] {
+        assert!(positions(column).iter().all(|&p| correct[p] == faulty[p]), "column {}", column);
+    }
+}
+
+#[test]
+fn unexplained_faults_give_no_key() {
+    let mut rng = Rng::new(11);
+    let aes = AES128::new(rng.bytes());
+    // a fault in round 7 reaches round 9 with every byte active
+    let pairs: Vec<FaultyPair> = (0..3).map(|i| faulty_pair(&aes, &mut rng, 7, i)).collect();
+    assert_eq!(last_round_key(&pairs), None);
+    assert_eq!(recover_key(&[]), None);
+}
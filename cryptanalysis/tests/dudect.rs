@@ -0,0 +1,96 @@
+//! Test: Welch's t-test with cropping tells equal timing distributions from shifted ones,
+//! including a shift hidden under outliers, and the harness catches the early exit of
+//! `smpc::leaky::array_equality` on real timings.
+
+use cryptanalysis::dudect::{analyse, check, measure, Class, Measurement, Welch, THRESHOLD};
+use cryptanalysis::rng::Rng;
+use smpc::{leaky_array_equality, GarbledNandGate, WireLabel, WireLabels};
+
+// Timings around 1000 cycles, `shift` slower for the random class, and every `outliers`-th
+// timing (if any) hit by a 10^6-cycle interrupt
+fn synthetic(count: usize, shift: u64, outliers: Option<u64>, seed: u64) -> Vec<Measurement> {
+    let mut rng = Rng::new(seed);
+    (0..count as u64)
+        .map(|i| {
+            let class = if rng.next_u64() >> 63 == 0 { Class::Fixed } else { Class::Random };
+            let mut time = 1000 + rng.next_u64() % 50 + if class == Class::Random { shift } else { 0 };
+            if outliers.is_some_and(|every| i % every == 0) {
+                time += 1_000_000 + rng.next_u64() % 1_000_000;
+            }
+            (class, time)
+        })
+        .collect()
+}
+
+#[test]
+fn welch_t_of_known_samples() {
+    let mut welch = Welch::default();
+    assert_eq!(welch.t(), 0.0);
+    for x in [1.0, 2.0, 3.0, 4.0] {
+        welch.push(Class::Fixed, x);
+        welch.push(Class::Random, x + 1.0);
+    }
+    // means 2.5 and 3.5, both variances 5/3 over 4 samples
+    assert!((welch.t() + 1.0 / (2.0 * 5.0 / 12.0f64).sqrt()).abs() < 1e-12);
+    assert_eq!(welch.count(), 8);
+}
+
+#[test]
+fn equal_distributions_show_no_leak() {
+    for seed in 1..=5 {
+        let report = analyse(&synthetic(50_000, 0, None, seed));
+        assert!(!report.leak_detected(), "seed {}: {}", seed, report);
+        assert_eq!(report.measurements, 50_000);
+    }
+}
+
+#[test]
+fn a_shift_of_one_cycle_is_a_leak() {
+    let report = analyse(&synthetic(50_000, 1, None, 1));
+    assert!(report.leak_detected(), "{}", report);
+    assert!(report.t < -THRESHOLD, "the random class is slower: {}", report);
+}
+
+#[test]
+fn cropping_finds_a_shift_under_outliers() {
+    let measurements = synthetic(50_000, 2, Some(50), 2);
+    let report = analyse(&measurements);
+    assert!(report.leak_detected(), "{}", report);
+    assert!(report.crop.is_some_and(|limit| limit < 1_000_000), "{}", report);
+
+    // without cropping the interrupts drown it
+    let mut all = Welch::default();
+    measurements.iter().for_each(|&(class, time)| all.push(class, time as f64));
+    assert!(all.t().abs() < THRESHOLD, "t = {}", all.t());
+}
+
+#[test]
+fn measure_times_every_input_of_both_classes() {
+    let mut calls = [0usize; 2];
+    let measurements = measure(1000, 7, |class, rng| (class, rng.next_u64()), |&(class, _)| calls[class as usize] += 1);
+    assert_eq!(measurements.len(), 1000);
+    let fixed = measurements.iter().filter(|(class, _)| *class == Class::Fixed).count();
+    assert!((400..600).contains(&fixed));
+    // the warm-up runs the first 1% once more
+    assert_eq!(calls[0] + calls[1], 1010);
+}
+
+#[test]
+fn leaky_array_equality_is_caught() {
+    let gates: Vec<GarbledNandGate> = (0..16).map(|_| GarbledNandGate::new()).collect();
+    let alice_labels: Vec<WireLabels> = gates.iter().map(|gate| gate.x_labels.clone()).collect();
+    let bob_labels: Vec<WireLabels> = gates.iter().map(|gate| gate.y_labels.clone()).collect();
+    let alice_inputs: Vec<WireLabel> = alice_labels.iter().map(|labels| labels.one.clone()).collect();
+    let output_labels = gates[0].z_labels.clone();
+
+    // equal arrays run to the end, random ones stop at the first difference
+    let input = |class: Class, rng: &mut Rng| -> Vec<WireLabel> {
+        let bit = |rng: &mut Rng| class == Class::Fixed || rng.next_u64() & 1 == 1;
+        bob_labels.iter().map(|labels| if bit(rng) { labels.one.clone() } else { labels.zero.clone() }).collect()
+    };
+    let report = check(1000, 1, input, |bob_inputs| {
+        leaky_array_equality(&alice_labels, &bob_labels, &alice_inputs, bob_inputs, &output_labels);
+    });
+    assert!(report.leak_detected(), "{}", report);
+    assert!(report.means[0] > report.means[1], "{}", report);
+}
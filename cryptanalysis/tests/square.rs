@@ -0,0 +1,69 @@
+//! Test: the Square attack recovers random AES-128 keys from 4-round ciphers, with and without
+//! the last MixColumns, and the 5-round attack works when run in release mode.
+
+use aes::{BlockCipher, ReducedAES128, AES128};
+use cryptanalysis::rng::Rng;
+use cryptanalysis::square::{attack_4_rounds, attack_5_rounds, lambda_set};
+
+// Random keys, so failures can be reproduced from the seed
+fn random_key(seed: u64) -> [u8; 16] {
+    Rng::new(seed).bytes()
+}
+
+fn oracle(cipher: &ReducedAES128) -> impl Fn(&[u8; 16]) -> [u8; 16] + '_ {
+    move |plaintext| {
+        let mut block = *plaintext;
+        cipher.encrypt_block(&mut block);
+        block
+    }
+}
+
+#[test]
+fn three_rounds_balance_every_byte() {
+    let cipher = ReducedAES128::with_final_mix_columns(random_key(1), 3);
+    for active in [0, 7, 15] {
+        let sum = lambda_set(&[0x42; 16], active).iter().map(oracle(&cipher)).fold([0u8; 16], |mut sum, c| {
+            sum.iter_mut().zip(c).for_each(|(s, c)| *s ^= c);
+            sum
+        });
+        assert_eq!(sum, [0; 16], "active byte {}", active);
+    }
+}
+
+#[test]
+fn recovers_random_keys_from_4_rounds() {
+    for seed in 1..=8 {
+        let key = random_key(seed);
+        let cipher = ReducedAES128::new(key, 4);
+        assert_eq!(attack_4_rounds(&oracle(&cipher), false), Some(key), "seed {}", seed);
+    }
+}
+
+#[test]
+fn recovers_random_keys_with_a_final_mix_columns() {
+    for seed in 100..104 {
+        let key = random_key(seed);
+        let cipher = ReducedAES128::with_final_mix_columns(key, 4);
+        assert_eq!(attack_4_rounds(&oracle(&cipher), true), Some(key), "seed {}", seed);
+    }
+}
+
+#[test]
+fn rejects_the_wrong_round_count() {
+    // full AES-128 has no balanced bytes to find, so some byte runs out of candidates
+    let cipher = AES128::new(random_key(7));
+    let oracle = |plaintext: &[u8; 16]| {
+        let mut block = *plaintext;
+        cipher.encrypt_block(&mut block);
+        block
+    };
+    assert_eq!(attack_4_rounds(&oracle, false), None);
+}
+
+#[test]
+#[ignore = "2^33 encryptions, run with `cargo test --release -- --ignored`"]
+fn recovers_a_random_key_from_5_rounds() {
+    let key = random_key(5);
+    let cipher = ReducedAES128::new(key, 5);
+    assert_eq!(attack_5_rounds(&oracle(&cipher), false), Some(key));
+}
@@ -0,0 +1,95 @@
+//! Test: the timing victims encrypt correctly in-process and over localhost TCP, and the
+//! correlator ranks the right key bytes near the top for a simulated leaky implementation.
+
+use std::io;
+use std::net::TcpListener;
+use std::thread;
+
+use aes::{BlockCipher, AES128};
+use cryptanalysis::rng::Rng;
+use cryptanalysis::timing::{
+    correlate, handle, remaining_key_bits, LocalVictim, Profile, RemoteVictim, TimingOracle, WARM_UP,
+};
+
+const KEY: [u8; 16] = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+
+// Takes longer depending on the 16-entry "cache line" each first-round index falls in, plus
+// noise, the way Bernstein's measurements behave
+struct LeakyOracle {
+    key: [u8; 16],
+    noise: Rng,
+}
+
+impl TimingOracle for LeakyOracle {
+    fn encrypt_timed(&mut self, plaintext: &[u8; 16]) -> io::Result<([u8; 16], u64)> {
+        let delay: u64 = plaintext.iter().zip(self.key).map(|(p, k)| ((p ^ k) >> 4) as u64 * 7 % 11).sum();
+        Ok(([0; 16], 200 + delay + (self.noise.next_u64() >> 59)))
+    }
+}
+
+fn expected_ciphertext(plaintext: &[u8; 16]) -> [u8; 16] {
+    let mut block = *plaintext;
+    AES128::new(KEY).encrypt_block(&mut block);
+    block
+}
+
+#[test]
+fn local_victim_encrypts_and_counts_cycles() {
+    let mut victim = LocalVictim::new(KEY);
+    let plaintext = [0x42; 16];
+    let (ciphertext, cycles) = victim.encrypt_timed(&plaintext).unwrap();
+    assert_eq!(ciphertext, expected_ciphertext(&plaintext));
+    assert!(cycles > 0);
+}
+
+#[test]
+fn tcp_victim_answers_every_request() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        handle(stream, &LocalVictim::new(KEY))
+    });
+
+    let mut victim = RemoteVictim::connect(address).unwrap();
+    for i in 0..100u8 {
+        let plaintext = [i; 16];
+        let (ciphertext, cycles) = victim.encrypt_timed(&plaintext).unwrap();
+        assert_eq!(ciphertext, expected_ciphertext(&plaintext));
+        assert!(cycles > 0);
+    }
+    drop(victim);
+    server.join().unwrap().unwrap();
+}
+
+#[test]
+fn correlation_finds_the_line_of_every_key_byte() {
+    let known_key = [0u8; 16];
+    let study = Profile::collect(&mut LeakyOracle { key: known_key, noise: Rng::new(1) }, 1 << 16, 1).unwrap();
+    let attack = Profile::collect(&mut LeakyOracle { key: KEY, noise: Rng::new(2) }, 1 << 16, 2).unwrap();
+    let rankings = correlate(&study, &known_key, &attack);
+
+    // the delay only depends on the top 4 bits, so the 16 guesses in the right line tie
+    for (position, ranking) in rankings.iter().enumerate() {
+        assert!(ranking.rank_of(KEY[position]) < 16, "byte {} ranked {}", position, ranking.rank_of(KEY[position]));
+        let top: Vec<u8> = ranking.candidates[..16].iter().map(|&(k, _)| k >> 4).collect();
+        assert!(top.iter().all(|&line| line == KEY[position] >> 4), "byte {}", position);
+    }
+    assert!(remaining_key_bits(&rankings, &KEY) <= 64.0);
+}
+
+#[test]
+fn outliers_are_dropped() {
+    struct Interrupted(u64);
+    impl TimingOracle for Interrupted {
+        fn encrypt_timed(&mut self, _: &[u8; 16]) -> io::Result<([u8; 16], u64)> {
+            self.0 += 1;
+            // every 100th timing is hit by an "interrupt" after the warm-up
+            let interrupted = self.0 > WARM_UP as u64 && self.0.is_multiple_of(100);
+            Ok(([0; 16], if interrupted { 1_000_000 } else { 300 }))
+        }
+    }
+    let profile = Profile::collect(&mut Interrupted(0), 10_000, 3).unwrap();
+    assert_eq!(profile.discarded, 100);
+    assert!(profile.deviations().iter().flatten().all(|d| d.abs() < 1e-9));
+}
@@ -1,29 +1,28 @@
-use crate::{set1::xor::xor_bytes, set2::padding::{pkcs7_pad, pkcs7_unpad}, set2::{aes_128_ecb_encrypt, aes_128_ecb_decrypt}};
+use aes::{BlockCipher, CbcDecryptor, CbcEncryptor, Padding, AES128};
 
-pub fn cbc_encrypt(plaintext: &[u8], key: &[u8; 16], iv: Option<&[u8; 16]>) -> Vec<u8> {
-    let mut cipher_text = Vec::new();
-    
-    // Default IV to all zeros
-    let iv = iv.unwrap_or(&[0; 16]);
+use crate::set2::padding::pkcs7_unpad;
 
-    // Pad the plaintext to a multiple of the block size
-    let padded_plaintext = pkcs7_pad(plaintext, 16);
-
-    let blocks = padded_plaintext.chunks_exact(16).collect::<Vec<&[u8]>>();
-
-    // First block: XOR with IV, then encrypt
-    let xor_result = xor_bytes(blocks[0], iv);
-    let mut prev_cipher_block = aes_128_ecb_encrypt(&xor_result, key);
-    cipher_text.extend_from_slice(&prev_cipher_block);
+/// CBC-encrypt with any block cipher. The plaintext is PKCS#7 padded to the cipher's block size,
+/// `iv` must be exactly one block long.
+pub fn cbc_encrypt_with<C: BlockCipher + ?Sized>(cipher: &C, plaintext: &[u8], iv: &[u8]) -> Vec<u8> {
+    let mut encryptor = CbcEncryptor::new(cipher, iv, Padding::Pkcs7);
+    let mut cipher_text = encryptor.update(plaintext);
+    cipher_text.extend(encryptor.finalize().expect("PKCS#7 padding always completes the block"));
+    cipher_text
+}
 
-    // Subsequent blocks: XOR with previous cipher_text block, then encrypt
-    for plain_block in blocks.iter().skip(1) {
-        let xor_result = xor_bytes(plain_block, &prev_cipher_block);
-        prev_cipher_block = aes_128_ecb_encrypt(&xor_result, key);
-        cipher_text.extend_from_slice(&prev_cipher_block);
-    }
+/// CBC-decrypt with any block cipher, without removing the padding.
+/// `iv` must be exactly one block long; a trailing partial block is ignored.
+pub fn cbc_decrypt_with<C: BlockCipher + ?Sized>(cipher: &C, cipher_text: &[u8], iv: &[u8]) -> Vec<u8> {
+    let whole = cipher_text.len() - cipher_text.len() % cipher.block_size();
+    // padding is checked by the callers (the padding oracle needs the raw result)
+    CbcDecryptor::new(cipher, iv, Padding::None).update(&cipher_text[..whole])
+}
 
-    cipher_text
+pub fn cbc_encrypt(plaintext: &[u8], key: &[u8; 16], iv: Option<&[u8; 16]>) -> Vec<u8> {
+    // Default IV to all zeros
+    let iv = iv.unwrap_or(&[0; 16]);
+    cbc_encrypt_with(&AES128::new(*key), plaintext, iv)
 }
 
 pub fn cbc_decrypt(
@@ -32,30 +31,13 @@ pub fn cbc_decrypt(
     iv: Option<&[u8; 16]>,
     unpad: Option<bool>,
 ) -> Vec<u8> {
-    let mut plaintext = Vec::new();
-
     // Default IV to all zeros
     let iv = iv.unwrap_or(&[0; 16]);
-
-    let blocks = cipher_text.chunks_exact(16).collect::<Vec<&[u8]>>();
-
-    let decrypted_block = aes_128_ecb_decrypt(blocks[0], key);
-    let xor_result = xor_bytes(&decrypted_block, iv);
-    plaintext.extend_from_slice(&xor_result);
-
-    let mut prev_cipher_block = blocks[0].to_vec();
-
-    for cipher_block in blocks.iter().skip(1) {
-        let decrypted_block = aes_128_ecb_decrypt(cipher_block, key);
-        let xor_result = xor_bytes(&decrypted_block, &prev_cipher_block);
-        plaintext.extend_from_slice(&xor_result);
-
-        prev_cipher_block = cipher_block.to_vec();
-    }
+    let plaintext = cbc_decrypt_with(&AES128::new(*key), cipher_text, iv);
 
     if unpad.unwrap_or(true) {
         pkcs7_unpad(&plaintext)
     } else {
         plaintext
     }
-}
\ No newline at end of file
+}
@@ -1,27 +1,31 @@
-use crate::set1::xor::xor_bytes;
-use aes::aes_128_ecb_encrypt;
+use aes::{parallel, BlockCipher, Ctr, Endianness, AES128};
 
 /// Fixed nonce of zero (for challenges / tests). Do not reuse in production.
 pub const NONCE_ZERO: [u8; 8] = [0u8; 8];
 
-/// CTR mode: encrypt(nonce || counter) gives keystream; XOR with data.
-/// Counter is 64-bit little-endian. Same function for encrypt and decrypt.
+/// CTR mode with any block cipher: encrypt(nonce || counter) gives keystream; XOR with data.
+/// The nonce fills the first `block_size - 8` bytes and the counter is 64-bit little-endian.
+/// Same function for encrypt and decrypt.
+pub fn ctr_keystream_xor_with<C: BlockCipher + ?Sized>(cipher: &C, input: &[u8], nonce: &[u8]) -> Vec<u8> {
+    assert_eq!(nonce.len() + 8, cipher.block_size(), "nonce and 64-bit counter must fill one block");
+
+    let mut initial_block = nonce.to_vec();
+    initial_block.extend_from_slice(&0u64.to_le_bytes());
+    Ctr::new(cipher, &initial_block, 8, Endianness::Little).update(input)
+}
+
+/// `ctr_keystream_xor_with` split across `threads` worker threads, for large inputs.
+/// The output is identical to the single-threaded version.
+pub fn ctr_keystream_xor_parallel<C: BlockCipher + Sync + ?Sized>(cipher: &C, input: &[u8], nonce: &[u8], threads: usize) -> Vec<u8> {
+    assert_eq!(nonce.len() + 8, cipher.block_size(), "nonce and 64-bit counter must fill one block");
+
+    let mut initial_block = nonce.to_vec();
+    initial_block.extend_from_slice(&0u64.to_le_bytes());
+    parallel::ctr_xor(cipher, &initial_block, 8, Endianness::Little, input, threads)
+}
+
 fn ctr_keystream_xor(input: &[u8], key: &[u8; 16], nonce: &[u8; 8]) -> Vec<u8> {
-    let mut output = Vec::with_capacity(input.len());
-    let mut counter: u64 = 0;
-    let mut pos = 0;
-    while pos < input.len() {
-        let mut block = [0u8; 16];
-        block[..8].copy_from_slice(nonce);
-        block[8..16].copy_from_slice(&counter.to_le_bytes());
-        let keystream = aes_128_ecb_encrypt(&block, key);
-        let take = (input.len() - pos).min(16);
-        let xored = xor_bytes(&input[pos..pos + take], &keystream[..take]);
-        output.extend_from_slice(&xored);
-        pos += take;
-        counter += 1;
-    }
-    output
+    ctr_keystream_xor_with(&AES128::new(*key), input, nonce)
 }
 
 pub fn ctr_encrypt(plaintext: &[u8], key: &[u8; 16], nonce: &[u8; 8]) -> Vec<u8> {
@@ -31,4 +35,3 @@ pub fn ctr_encrypt(plaintext: &[u8], key: &[u8; 16], nonce: &[u8; 8]) -> Vec<u8>
 pub fn ctr_decrypt(ciphertext: &[u8], key: &[u8; 16], nonce: &[u8; 8]) -> Vec<u8> {
     ctr_keystream_xor(ciphertext, key, nonce)
 }
-
@@ -1,22 +1,25 @@
+use aes::BlockCipher;
+
 use crate::set2::padding::pkcs7_unpad;
 use crate::set3::utils::PaddingOracle;
 
-const BLOCK_SIZE: usize = 16;
-
 /// Recovers the full plaintext using the padding oracle.
 ///
-/// * `ciphertext`: full CBC ciphertext (multiple of 16 bytes).
-/// * `iv`: the IV used for encryption (16 bytes).
+/// * `ciphertext`: full CBC ciphertext (multiple of the oracle's block size).
+/// * `iv`: the IV used for encryption (one block).
 /// * `oracle`: the padding oracle (holds the key; attacker only gets boolean padding feedback).
-pub fn padding_oracle_attack(ciphertext: &[u8], iv: &[u8; 16], oracle: &PaddingOracle) -> Vec<u8> {
-    assert_eq!(ciphertext.len() % BLOCK_SIZE, 0);
-    let blocks: Vec<&[u8]> = ciphertext.chunks_exact(BLOCK_SIZE).collect();
+///   Only the block size of its cipher is used, so any `BlockCipher` can sit behind it.
+pub fn padding_oracle_attack<C: BlockCipher>(ciphertext: &[u8], iv: &[u8], oracle: &PaddingOracle<C>) -> Vec<u8> {
+    let block_size = oracle.block_size();
+    assert!(ciphertext.len().is_multiple_of(block_size));
+    assert_eq!(iv.len(), block_size);
+    let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
     let n = blocks.len();
     if n == 0 {
         return vec![];
     }
 
-    let mut plaintext_blocks: Vec<[u8; BLOCK_SIZE]> = Vec::with_capacity(n);
+    let mut plaintext_blocks: Vec<Vec<u8>> = Vec::with_capacity(n);
 
     // Decrypt from last block to first. For each block we only need that block and the
     // "previous" block (or IV for the first ciphertext block). We force valid padding
@@ -27,7 +30,7 @@ pub fn padding_oracle_attack(ciphertext: &[u8], iv: &[u8; 16], oracle: &PaddingO
     }
 
     // We collected [P_n, P_{n-1}, ..., P_1]; concatenate in correct order.
-    let mut ordered = Vec::with_capacity(n * BLOCK_SIZE);
+    let mut ordered = Vec::with_capacity(n * block_size);
     for block in plaintext_blocks.into_iter().rev() {
         ordered.extend_from_slice(&block);
     }
@@ -42,34 +45,31 @@ pub fn padding_oracle_attack(ciphertext: &[u8], iv: &[u8; 16], oracle: &PaddingO
 /// We control prev_block (call it G). So we set G so that P' has valid padding.
 /// When the oracle returns true, we know the last byte(s) of P'; that reveals
 /// the corresponding byte(s) of Dec(C), hence of the real plaintext P = Dec(C) XOR C_prev.
-fn decrypt_block(
+fn decrypt_block<C: BlockCipher>(
     block_index: usize,
     blocks: &[&[u8]],
-    iv: &[u8; 16],
-    oracle: &PaddingOracle,
-) -> [u8; BLOCK_SIZE] {
-    let prev_block: [u8; 16] = if block_index == 0 {
-        *iv
-    } else {
-        blocks[block_index - 1].try_into().unwrap()
-    };
+    iv: &[u8],
+    oracle: &PaddingOracle<C>,
+) -> Vec<u8> {
+    let block_size = iv.len();
+    let prev_block = if block_index == 0 { iv } else { blocks[block_index - 1] };
 
     // We will build G (modified previous block) and recover Dec(C) byte by byte.
     // Dec(C) is the raw block-cipher output before XOR with previous block.
-    let mut g = [0u8; BLOCK_SIZE];
-    let mut dec_c = [0u8; BLOCK_SIZE]; // Dec(current_block), filled from last byte to first
+    let mut g = vec![0u8; block_size];
+    let mut dec_c = vec![0u8; block_size]; // Dec(current_block), filled from last byte to first
 
-    // Decrypt from last byte (index 15) to first (index 0).
-    for pos in (0..BLOCK_SIZE).rev() {
+    // Decrypt from last byte (index block_size - 1) to first (index 0).
+    for pos in (0..block_size).rev() {
         // --- How padding is forced ---
         // We want the decrypted block P' = Dec(C) XOR G to end with valid PKCS#7:
-        // P'[pos..16] = L, L, ..., L with L = 16 - pos (so L in 1..=16).
-        // We already recovered dec_c[pos+1..16] in previous iterations. So we set
-        // G[j] = dec_c[j] XOR L for j in pos+1..16, giving P'[j] = Dec(C)[j] XOR G[j] = L.
-        let padding_len = (BLOCK_SIZE - pos) as u8;
+        // P'[pos..] = L, L, ..., L with L = block_size - pos (so L in 1..=block_size).
+        // We already recovered dec_c[pos+1..] in previous iterations. So we set
+        // G[j] = dec_c[j] XOR L for j in pos+1.., giving P'[j] = Dec(C)[j] XOR G[j] = L.
+        let padding_len = (block_size - pos) as u8;
 
-        for j in (pos + 1)..BLOCK_SIZE {
-            g[j] = dec_c[j] ^ padding_len;
+        for (g_byte, dec_byte) in g[pos + 1..].iter_mut().zip(&dec_c[pos + 1..]) {
+            *g_byte = dec_byte ^ padding_len;
         }
 
         // --- Why the oracle response leaks information ---
@@ -98,11 +98,7 @@ fn decrypt_block(
     }
 
     // Real plaintext: P = Dec(C) XOR prev_block (the actual previous ciphertext block or IV).
-    let mut plain = [0u8; BLOCK_SIZE];
-    for i in 0..BLOCK_SIZE {
-        plain[i] = dec_c[i] ^ prev_block[i];
-    }
-    plain
+    dec_c.iter().zip(prev_block).map(|(d, p)| d ^ p).collect()
 }
 
 /// Builds the ciphertext to send to the oracle and calls it.
@@ -112,20 +108,20 @@ fn decrypt_block(
 /// For block_index i > 0: we send iv = original iv, ciphertext = C_0 .. C_{i-2} || G || C_i,
 /// so the last decrypted block is P' = Dec(C_i) XOR G.
 /// The oracle only sees (ciphertext, iv); it uses the real key internally to decrypt and check padding.
-fn query_oracle(
+fn query_oracle<C: BlockCipher>(
     block_index: usize,
     blocks: &[&[u8]],
-    iv: &[u8; 16],
-    g: &[u8; 16],
-    oracle: &PaddingOracle,
+    iv: &[u8],
+    g: &[u8],
+    oracle: &PaddingOracle<C>,
 ) -> bool {
-    let mut ct = Vec::with_capacity((block_index + 1) * BLOCK_SIZE);
+    let mut ct = Vec::with_capacity((block_index + 1) * iv.len());
     if block_index == 0 {
         ct.extend_from_slice(blocks[0]);
         oracle.check_padding(&ct, g)
     } else {
-        for j in 0..block_index - 1 {
-            ct.extend_from_slice(blocks[j]);
+        for block in &blocks[..block_index - 1] {
+            ct.extend_from_slice(block);
         }
         ct.extend_from_slice(g);
         ct.extend_from_slice(blocks[block_index]);
@@ -1,6 +1,7 @@
+use aes::{BlockCipher, AES128};
 use rand::Rng;
 
-use crate::{set1::encoding::base64_decode, set2::{cbc::{cbc_decrypt, cbc_encrypt}, padding::pkcs7_validate}};
+use crate::{set1::encoding::base64_decode, set2::{cbc::{cbc_decrypt, cbc_decrypt_with, cbc_encrypt}, padding::pkcs7_validate}};
 
 pub const LINES: [&str; 10] = ["MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
 "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
@@ -22,8 +23,7 @@ pub fn get_random_line() -> String {
 pub fn random_cbc_encrypt(key: &[u8; 16], iv: &[u8; 16]) -> Vec<u8> {
     let plaintext = get_random_line();
     let bytes = base64_decode(plaintext.as_str());
-    let ciphertext = cbc_encrypt(&bytes, key, Some(iv));
-    ciphertext
+    cbc_encrypt(&bytes, key, Some(iv))
 }
 
 pub fn check_padding(ciphertext: &[u8], key: &[u8; 16], iv: &[u8; 16]) -> bool {
@@ -31,17 +31,29 @@ pub fn check_padding(ciphertext: &[u8], key: &[u8; 16], iv: &[u8; 16]) -> bool {
     pkcs7_validate(&raw_plaintext)
 }
 
-// Holds the key for the padding oracle attack.
-pub struct PaddingOracle {
-    key: [u8; 16],
+// Holds the cipher (and so the key) for the padding oracle attack.
+pub struct PaddingOracle<C: BlockCipher = AES128> {
+    cipher: C,
 }
 
 impl PaddingOracle {
     pub fn new(key: [u8; 16]) -> Self {
-        Self { key }
+        Self::with_cipher(AES128::new(key))
+    }
+}
+
+impl<C: BlockCipher> PaddingOracle<C> {
+    /// Oracle over an arbitrary block cipher, e.g. another AES backend or a toy cipher.
+    pub fn with_cipher(cipher: C) -> Self {
+        Self { cipher }
     }
 
-    pub fn check_padding(&self, ciphertext: &[u8], iv: &[u8; 16]) -> bool {
-        check_padding(ciphertext, &self.key, iv)
+    pub fn block_size(&self) -> usize {
+        self.cipher.block_size()
     }
-}
\ No newline at end of file
+
+    pub fn check_padding(&self, ciphertext: &[u8], iv: &[u8]) -> bool {
+        let raw_plaintext = cbc_decrypt_with(&self.cipher, ciphertext, iv);
+        pkcs7_validate(&raw_plaintext)
+    }
+}
@@ -47,3 +47,21 @@ fn test_challenge10_encrypt_then_decrypt() {
         "Encrypt then decrypt should yield original plaintext"
     );
 }
+
+#[test]
+fn test_challenge10_generic_cbc_matches_aes_wrapper() {
+    use aes::AES128;
+    use cryptopals::set2::cbc::{cbc_decrypt_with, cbc_encrypt_with};
+
+    let plaintext = b"Same bytes whichever entry point encrypts them";
+    let key: [u8; 16] = KEY.try_into().expect("key must be 16 bytes");
+    let iv = [7u8; 16];
+    let cipher = AES128::new(key);
+
+    let cipher_text = cbc_encrypt_with(&cipher, plaintext, &iv);
+    assert_eq!(cipher_text, cbc_encrypt(plaintext, &key, Some(&iv)));
+    assert_eq!(
+        cbc_decrypt_with(&cipher, &cipher_text, &iv),
+        cbc_decrypt(&cipher_text, &key, Some(&iv), Some(false))
+    );
+}
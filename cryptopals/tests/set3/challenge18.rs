@@ -1,4 +1,8 @@
-use cryptopals::{set1::encoding::base64_decode, set3::ctr::{ctr_decrypt, ctr_encrypt}};
+use aes::AES128;
+use cryptopals::{
+    set1::encoding::base64_decode,
+    set3::ctr::{ctr_decrypt, ctr_encrypt, ctr_keystream_xor_parallel, ctr_keystream_xor_with},
+};
 
 const INPUT_BASE64: &str = "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==";
 const KEY: &[u8; 16] = b"YELLOW SUBMARINE";
@@ -12,3 +16,14 @@ fn test_ctr_encrypt_decrypt() {
     let decrypted = ctr_decrypt(&ciphertext, KEY, NONCE);
     assert_eq!(plaintext, decrypted);
 }
+
+#[test]
+fn test_ctr_parallel_matches_serial() {
+    let aes = AES128::new(*KEY);
+    let plaintext: Vec<u8> = (0..5000u32).map(|i| (i * 7) as u8).collect();
+    let serial = ctr_keystream_xor_with(&aes, &plaintext, NONCE);
+    for threads in [1, 2, 4, 5] {
+        assert_eq!(ctr_keystream_xor_parallel(&aes, &plaintext, NONCE, threads), serial);
+    }
+    assert_eq!(ctr_encrypt(&plaintext, KEY, NONCE), serial);
+}